@@ -0,0 +1,61 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Build Script
+//!
+//! Embeds the SHA-256 checksums of the proving contexts shipped with the signer so that tampered
+//! or truncated proving contexts can be refused at runtime.
+
+use sha2::{Digest, Sha256};
+use std::{env, fmt::Write, fs, path::PathBuf};
+
+/// Proving Context File Names
+const PROVING_CONTEXTS: [&str; 3] = ["to-private.lfs", "private-transfer.lfs", "to-public.lfs"];
+
+fn main() {
+    let directory =
+        PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").expect("Cargo always sets this."))
+            .join("ui")
+            .join("src-tauri")
+            .join("proving");
+    let mut checksums = String::from("[");
+    for name in PROVING_CONTEXTS {
+        let path = directory.join(name);
+        println!("cargo:rerun-if-changed={}", path.display());
+        match fs::read(&path) {
+            Ok(data) => write!(
+                checksums,
+                "({name:?}, Some({:?})),",
+                Sha256::digest(data).as_slice()
+            ),
+            Err(_) => {
+                println!(
+                    "cargo:warning={} is missing so it will be refused at runtime",
+                    path.display()
+                );
+                write!(checksums, "({name:?}, None),")
+            }
+        }
+        .expect("Writing to a string cannot fail.");
+    }
+    checksums.push(']');
+    fs::write(
+        PathBuf::from(env::var_os("OUT_DIR").expect("Cargo always sets this."))
+            .join("proving_checksums.rs"),
+        checksums,
+    )
+    .expect("Unable to write the proving context checksums.");
+}
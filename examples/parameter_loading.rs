@@ -0,0 +1,69 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Parameter Loading Benchmark
+//!
+//! Compares the time it takes to load the signer parameters with a cold proving context cache,
+//! which decodes the proving contexts from their source files and fills the cache, against a
+//! warm one. The proving contexts are read from the directory given as the first argument, or
+//! from the ones shipped with the signer. On Linux, the peak resident set size is printed after
+//! each step.
+
+use manta_signer::parameters::{self, ParameterError};
+use std::{path::PathBuf, time::Instant};
+
+/// Returns the peak resident set size of the process, if the platform reports it.
+fn peak_rss() -> Option<String> {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()?
+        .lines()
+        .find_map(|line| Some(line.strip_prefix("VmHWM:")?.trim().to_owned()))
+}
+
+/// Prints the time elapsed since `started` and the peak resident set size for `step`.
+fn report(step: &str, started: Instant) {
+    println!(
+        "{step:<14} {:?} (peak RSS: {})",
+        started.elapsed(),
+        peak_rss().unwrap_or_else(|| "unknown".into())
+    );
+}
+
+fn main() -> Result<(), ParameterError> {
+    let proving_directory = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            [env!("CARGO_MANIFEST_DIR"), "ui", "src-tauri", "proving"]
+                .iter()
+                .collect()
+        });
+    let cache_directory =
+        tempfile::tempdir().expect("Unable to create the proving context cache directory.");
+    let started = Instant::now();
+    parameters::load(&proving_directory, None)?;
+    report("without cache:", started);
+    let started = Instant::now();
+    parameters::load(&proving_directory, Some(cache_directory.path()))?;
+    report("cold cache:", started);
+    let started = Instant::now();
+    let parameters = parameters::load(&proving_directory, Some(cache_directory.path()))?;
+    report("warm cache:", started);
+    let started = Instant::now();
+    let _copy = parameters.clone();
+    report("second copy:", started);
+    Ok(())
+}
@@ -17,28 +17,41 @@
 //! Test Signer Server
 
 use manta_crypto::rand::{CryptoRng, OsRng, RngCore, Sample};
-use manta_pay::{key::Mnemonic, signer::client::network::Network};
+use manta_pay::key::Mnemonic;
 use manta_signer::{
-    config::{Config, Setup},
-    secret::{Authorizer, Password, PasswordFuture, SecretString, SetupFuture},
+    config::{Config, Setup, DEFAULT_PROFILE},
+    mnemonic,
+    secret::{
+        Authorizer, DivergenceChoice, DivergenceFuture, Password, PasswordFuture, SecretString,
+        SetupFuture, StateDivergence,
+    },
     service::{Error, Server},
 };
 
+/// Recovery Phrase Variable
+///
+/// Environment variable holding a recovery phrase to recover instead of creating a new account.
+const MNEMONIC_VARIABLE: &str = "MANTA_SIGNER_MNEMONIC";
+
 /// Mock User
 pub struct MockUser {
     /// User Password
     password: SecretString,
+
+    /// Recovery Phrase
+    mnemonic: Option<Mnemonic>,
 }
 
 impl MockUser {
-    /// Builds a new [`MockUser`] from `password`.
+    /// Builds a new [`MockUser`] with a random password, recovering `mnemonic` if it is set.
     #[inline]
-    pub fn new<R>(rng: &mut R) -> Self
+    pub fn new<R>(rng: &mut R, mnemonic: Option<Mnemonic>) -> Self
     where
         R: CryptoRng + RngCore + ?Sized,
     {
         Self {
             password: SecretString::new(u128::gen(rng).to_string()),
+            mnemonic,
         }
     }
 }
@@ -51,12 +64,20 @@ impl Authorizer for MockUser {
 
     #[inline]
     fn setup(&mut self, data_exists: bool) -> SetupFuture {
-        let new_mnemonic = Mnemonic::sample(&mut OsRng);
         if data_exists {
-            Box::pin(async move { Setup::Login })
-        } else {
-            Box::pin(async move { Setup::CreateAccount(new_mnemonic) })
+            return Box::pin(async move { Setup::Login });
         }
+        let setup = match self.mnemonic.clone() {
+            Some(mnemonic) => Setup::create_account(mnemonic),
+            _ => Setup::create_account(Mnemonic::sample(&mut OsRng)),
+        };
+        Box::pin(async move { setup })
+    }
+
+    #[inline]
+    fn state_divergence(&mut self, divergence: &StateDivergence) -> DivergenceFuture {
+        let _ = divergence;
+        Box::pin(async move { DivergenceChoice::Repair })
     }
 }
 
@@ -65,13 +86,23 @@ async fn main() -> Result<(), Error> {
     let test_dir = tempfile::tempdir()?;
     let mut config =
         Config::try_default().expect("Unable to generate the default server configuration.");
-    config.data_path[Network::Dolphin] = test_dir.path().join("storage-dolphin.dat");
-    config.data_path[Network::Calamari] = test_dir.path().join("storage-calamari.dat");
-    config.data_path[Network::Manta] = test_dir.path().join("storage-manta.dat");
+    config.root_directory = test_dir.path().to_owned();
+    let mut config = config
+        .with_profile(DEFAULT_PROFILE)
+        .expect("The default profile name is always valid.");
+    config.use_bundled_proving_directory(
+        [env!("CARGO_MANIFEST_DIR"), "ui", "src-tauri", "proving"]
+            .iter()
+            .collect(),
+    );
     if let Some(url) = std::env::args().nth(1) {
         config.service_url = url;
     }
-    Server::build(config, MockUser::new(&mut OsRng))
+    let mnemonic = std::env::var(MNEMONIC_VARIABLE).ok().map(|phrase| {
+        mnemonic::parse(&phrase)
+            .unwrap_or_else(|err| panic!("Invalid recovery phrase in {MNEMONIC_VARIABLE}: {err}"))
+    });
+    Server::build(config, MockUser::new(&mut OsRng, mnemonic))
         .await?
         .start()
         .await
@@ -0,0 +1,157 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Address Book
+//!
+//! The address book maps recipient addresses to user-chosen labels so that authorization prompts
+//! can name the recipient instead of only showing its base58 encoding. It is stored encrypted
+//! under the same password key as the signer state and can only be modified locally, never by the
+//! dApp.
+
+use alloc::collections::BTreeMap;
+use manta_pay::config::{address_from_base58, address_to_base58};
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Number of characters kept on each side of an abbreviated address.
+const ABBREVIATION_LENGTH: usize = 4;
+
+/// Returns the canonical base58 encoding of `address` if it decodes to a valid address, which is
+/// the encoding that transactions to it are compared against.
+#[inline]
+pub fn canonical_address(address: &str) -> Option<String> {
+    let address = String::from(address);
+    address_from_base58(&address).map(|address| address_to_base58(&address))
+}
+
+/// Abbreviates the base58 `address` to its first and last few characters.
+#[inline]
+pub fn abbreviate(address: &str) -> String {
+    if address.len() <= 2 * ABBREVIATION_LENGTH {
+        return address.to_owned();
+    }
+    format!(
+        "{}…{}",
+        &address[..ABBREVIATION_LENGTH],
+        &address[address.len() - ABBREVIATION_LENGTH..]
+    )
+}
+
+/// Address Book Entry
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct AddressBookEntry {
+    /// Base58-Encoded Address
+    pub address: String,
+
+    /// Label
+    pub label: String,
+}
+
+/// Address Book
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct AddressBook {
+    /// Labels keyed by Base58-Encoded Address
+    labels: BTreeMap<String, String>,
+}
+
+impl AddressBook {
+    /// Returns the label for the base58-encoded `address` if it is known.
+    #[inline]
+    pub fn label(&self, address: &str) -> Option<&str> {
+        self.labels.get(address).map(String::as_str)
+    }
+
+    /// Sets the label for the base58-encoded `address` to `label`, returning the previous label if
+    /// there was one.
+    #[inline]
+    pub fn insert(&mut self, address: String, label: String) -> Option<String> {
+        self.labels.insert(address, label)
+    }
+
+    /// Removes the label for the base58-encoded `address`, returning it if there was one.
+    #[inline]
+    pub fn remove(&mut self, address: &str) -> Option<String> {
+        self.labels.remove(address)
+    }
+
+    /// Returns all the entries of the address book, ordered by address.
+    #[inline]
+    pub fn entries(&self) -> Vec<AddressBookEntry> {
+        self.labels
+            .iter()
+            .map(|(address, label)| AddressBookEntry {
+                address: address.clone(),
+                label: label.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns the human-readable form of the base58-encoded `address`, which is its label
+    /// followed by the abbreviated address if it is known, or the full address otherwise.
+    #[inline]
+    pub fn display(&self, address: &str) -> String {
+        match self.label(address) {
+            Some(label) => format!("{label} ({})", abbreviate(address)),
+            _ => address.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Checks that strings which are not base58 encodings of an address are rejected.
+    #[test]
+    fn rejects_invalid_addresses() {
+        for address in [
+            "",
+            "0OIl",
+            "not base58",
+            "3yZe7d",
+            "11111111111111111111111111111111111111111111111111111111111111111111",
+        ] {
+            assert_eq!(canonical_address(address), None, "{address:?}");
+        }
+    }
+
+    /// Checks that long addresses are abbreviated and short ones are kept.
+    #[test]
+    fn abbreviates_addresses() {
+        assert_eq!(abbreviate("abcdefgh"), "abcdefgh");
+        assert_eq!(abbreviate("abcdefghijkl"), "abcd…ijkl");
+    }
+
+    /// Checks that labels are shown with the abbreviated address and can be removed.
+    #[test]
+    fn labels_addresses() {
+        let mut address_book = AddressBook::default();
+        let address = "3yZe7dkA1a2b3c4d5e6f".to_owned();
+        assert_eq!(address_book.display(&address), address);
+        assert_eq!(address_book.insert(address.clone(), "Alice".into()), None);
+        assert_eq!(address_book.display(&address), "Alice (3yZe…5e6f)");
+        assert_eq!(
+            address_book.entries(),
+            vec![AddressBookEntry {
+                address: address.clone(),
+                label: "Alice".into(),
+            }]
+        );
+        assert_eq!(address_book.remove(&address), Some("Alice".into()));
+        assert_eq!(address_book.label(&address), None);
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Asset Utilities
+
+use manta_pay::config::AssetId;
+use manta_util::codec::Encode;
+
+/// Converts the field-encoded asset `id` into the numeric asset id used on-chain, returning
+/// `None` if `id` does not fit into a `u128`.
+#[inline]
+pub fn id_to_u128(id: &AssetId) -> Option<u128> {
+    let bytes = id.to_vec();
+    if bytes.len() < 16 || bytes[16..].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    let mut low = [0; 16];
+    low.copy_from_slice(&bytes[..16]);
+    Some(u128::from_le_bytes(low))
+}
+
+/// Returns a human-readable representation of the asset `id`, using the numeric asset id when
+/// possible and falling back to the hex encoding of the field element otherwise.
+#[inline]
+pub fn display_id(id: &AssetId) -> String {
+    match id_to_u128(id) {
+        Some(id) => id.to_string(),
+        _ => id
+            .to_vec()
+            .iter()
+            .rev()
+            .fold(String::from("0x"), |mut hex, byte| {
+                hex.push_str(&format!("{byte:02x}"));
+                hex
+            }),
+    }
+}
@@ -0,0 +1,292 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Password Attempts
+//!
+//! Failed password attempts are persisted in the profile directory so that restarting the signer
+//! does not reset the delay between attempts. After a few free attempts the delay doubles with
+//! every failure, and after [`LOCKOUT_THRESHOLD`] failures the password is not checked at all
+//! until the [`LOCKOUT_DURATION`] has passed.
+
+use core::time::Duration;
+use manta_util::serde::{Deserialize, Serialize};
+use std::{
+    io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::fs;
+
+/// Number of Failures before the Delay Starts Growing
+pub const FREE_ATTEMPTS: u32 = 3;
+
+/// Base Delay between Attempts
+pub const BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Maximum Delay between Attempts before the Lockout
+pub const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// Number of Failures which Lock the Password Out
+pub const LOCKOUT_THRESHOLD: u32 = 10;
+
+/// Lockout Duration
+pub const LOCKOUT_DURATION: Duration = Duration::from_secs(30 * 60);
+
+/// Returns the current UNIX time in seconds.
+#[inline]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default()
+}
+
+/// Password Delay
+///
+/// Tells the user how long they have to wait before their next password is checked.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct PasswordDelay {
+    /// Remaining Delay in Seconds
+    pub seconds: u64,
+
+    /// Lockout Flag
+    ///
+    /// Passwords sent during a lockout are rejected without being checked, instead of waiting for
+    /// the delay to pass.
+    pub lockout: bool,
+}
+
+/// Password Attempts
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct PasswordAttempts {
+    /// Number of Consecutive Failures
+    pub failures: u32,
+
+    /// UNIX Time of the Last Failure in Seconds
+    pub last_failure: Option<u64>,
+
+    /// Number of Failures not yet Reported to the User
+    ///
+    /// Unlike [`failures`](Self::failures), this is only reset once the user has been told about
+    /// the failures on a successful login.
+    pub unreported: u32,
+}
+
+impl PasswordAttempts {
+    /// Loads the password attempts from `path`, returning no failures if the file does not
+    /// exist yet.
+    #[inline]
+    pub async fn load<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        match fs::read(path).await {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Saves the password attempts to `path`.
+    #[inline]
+    pub async fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, serde_json::to_vec_pretty(self)?).await
+    }
+
+    /// Returns the delay which has to pass after the last failure before the password is checked
+    /// again.
+    #[inline]
+    pub fn delay(&self) -> Duration {
+        match self.failures {
+            0 => Duration::ZERO,
+            failures if failures >= LOCKOUT_THRESHOLD => LOCKOUT_DURATION,
+            failures if failures <= FREE_ATTEMPTS => BASE_DELAY,
+            failures => BASE_DELAY
+                .saturating_mul(1 << (failures - FREE_ATTEMPTS).min(16))
+                .min(MAX_BACKOFF),
+        }
+    }
+
+    /// Returns the part of the [`delay`](Self::delay) which has not passed yet.
+    ///
+    /// A last failure in the future, after the system clock was set back, counts as having just
+    /// happened, so the remaining delay is never longer than the full delay.
+    #[inline]
+    pub fn remaining(&self) -> Duration {
+        match self.last_failure {
+            Some(last_failure) => self
+                .delay()
+                .saturating_sub(Duration::from_secs(now().saturating_sub(last_failure))),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Returns the [`PasswordDelay`] to show to the user, rounding the remaining delay up to the
+    /// next second.
+    #[inline]
+    pub fn password_delay(&self) -> PasswordDelay {
+        let remaining = self.remaining();
+        PasswordDelay {
+            seconds: remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0),
+            lockout: self.is_locked_out(),
+        }
+    }
+
+    /// Returns `true` if there were too many failures and the lockout has not ended yet.
+    #[inline]
+    pub fn is_locked_out(&self) -> bool {
+        self.failures >= LOCKOUT_THRESHOLD && !self.remaining().is_zero()
+    }
+
+    /// Records a failed attempt.
+    #[inline]
+    pub fn record_failure(&mut self) {
+        self.failures = self.failures.saturating_add(1);
+        self.unreported = self.unreported.saturating_add(1);
+        self.last_failure = Some(now());
+    }
+
+    /// Records a successful attempt, which resets the delay.
+    #[inline]
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+        self.last_failure = None;
+    }
+
+    /// Returns the number of failures which were not reported to the user yet and marks them as
+    /// reported.
+    #[inline]
+    pub fn take_unreported(&mut self) -> u32 {
+        core::mem::take(&mut self.unreported)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns password attempts with `failures` failures, the last of which happened `ago`
+    /// seconds ago.
+    #[inline]
+    fn attempts(failures: u32, ago: u64) -> PasswordAttempts {
+        PasswordAttempts {
+            failures,
+            last_failure: Some(now() - ago),
+            unreported: failures,
+        }
+    }
+
+    /// Checks that the delay is constant for the free attempts, then doubles up to the maximum
+    /// backoff and jumps to the lockout duration at the threshold.
+    #[test]
+    fn delays_grow_with_failures() {
+        assert_eq!(PasswordAttempts::default().delay(), Duration::ZERO);
+        for failures in 1..=FREE_ATTEMPTS {
+            assert_eq!(attempts(failures, 0).delay(), BASE_DELAY);
+        }
+        assert_eq!(attempts(FREE_ATTEMPTS + 1, 0).delay(), BASE_DELAY * 2);
+        assert_eq!(attempts(FREE_ATTEMPTS + 2, 0).delay(), BASE_DELAY * 4);
+        let mut previous = Duration::ZERO;
+        for failures in 0..LOCKOUT_THRESHOLD {
+            let delay = attempts(failures, 0).delay();
+            assert!(previous <= delay && delay <= MAX_BACKOFF, "{failures}");
+            previous = delay;
+        }
+        assert_eq!(attempts(LOCKOUT_THRESHOLD, 0).delay(), LOCKOUT_DURATION);
+        assert_eq!(attempts(u32::MAX, 0).delay(), LOCKOUT_DURATION);
+    }
+
+    /// Checks that the remaining delay shrinks as time passes and that the lockout ends with it.
+    #[test]
+    fn lockout_expires() {
+        let locked = attempts(LOCKOUT_THRESHOLD, 60);
+        assert!(locked.is_locked_out());
+        let delay = locked.password_delay();
+        assert!(delay.lockout);
+        // NOTE: A second may pass between recording the failure and reading the delay.
+        assert!(
+            (LOCKOUT_DURATION.as_secs() - 61..=LOCKOUT_DURATION.as_secs() - 60)
+                .contains(&delay.seconds)
+        );
+        let expired = attempts(LOCKOUT_THRESHOLD, LOCKOUT_DURATION.as_secs());
+        assert!(!expired.is_locked_out());
+        assert_eq!(
+            expired.password_delay(),
+            PasswordDelay {
+                seconds: 0,
+                lockout: false,
+            }
+        );
+        assert!(!attempts(LOCKOUT_THRESHOLD - 1, 0).is_locked_out());
+    }
+
+    /// Checks that a last failure in the future does not make the delay longer than the full
+    /// delay.
+    #[test]
+    fn caps_delay_after_clock_change() {
+        let mut attempts = attempts(FREE_ATTEMPTS + 1, 0);
+        attempts.last_failure = Some(now() + 24 * 60 * 60);
+        assert_eq!(attempts.remaining(), attempts.delay());
+    }
+
+    /// Checks that a success resets the delay but keeps the failures to report until they are
+    /// taken.
+    #[test]
+    fn reports_failures_once() {
+        let mut attempts = PasswordAttempts::default();
+        attempts.record_failure();
+        attempts.record_failure();
+        assert_eq!(attempts.failures, 2);
+        assert!(attempts.last_failure.is_some());
+        attempts.record_success();
+        assert_eq!(attempts.delay(), Duration::ZERO);
+        assert_eq!(attempts.remaining(), Duration::ZERO);
+        assert_eq!(attempts.take_unreported(), 2);
+        assert_eq!(attempts.take_unreported(), 0);
+    }
+
+    /// Checks that password attempts are saved and loaded back, and that a missing file loads as
+    /// no failures.
+    #[test]
+    fn persists_attempts() {
+        let directory = tempfile::tempdir().expect("Unable to create temporary directory.");
+        let path = directory.path().join("attempts.json");
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("Unable to build runtime.")
+            .block_on(async {
+                assert_eq!(
+                    PasswordAttempts::load(&path)
+                        .await
+                        .expect("Unable to load."),
+                    PasswordAttempts::default()
+                );
+                let attempts = attempts(4, 10);
+                attempts.save(&path).await.expect("Unable to save.");
+                assert_eq!(
+                    PasswordAttempts::load(&path)
+                        .await
+                        .expect("Unable to load."),
+                    attempts
+                );
+            });
+    }
+}
@@ -0,0 +1,410 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Authorization Requests
+
+use crate::{
+    address_book::AddressBook,
+    asset,
+    network::NetworkId,
+    registry::{RegisteredAsset, RegisteredType},
+};
+use manta_accounting::{asset::Asset, transfer::canonical::TransferShape};
+use manta_pay::{
+    config::{address_to_base58, Transaction},
+    signer::{AssetMetadata, TokenType},
+};
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Returns a summary of the current transaction, naming the recipient with its label from
+/// `address_book` if it has one and flagging it if `is_new_recipient` is set.
+#[inline]
+pub fn display_transaction(
+    transaction: &Transaction,
+    metadata: &AssetMetadata,
+    network: &NetworkId,
+    address_book: &AddressBook,
+    is_new_recipient: bool,
+) -> String {
+    match transaction {
+        Transaction::ToPrivate(Asset { value, .. }) => match metadata.token_type {
+            TokenType::FT(decimals) => {
+                format!(
+                    "Privatize {} on {} network",
+                    metadata.display(*value, decimals),
+                    network
+                )
+            }
+            TokenType::NFT => {
+                format!("Privatize NFT on {} network", network)
+            }
+        },
+        Transaction::PrivateTransfer(Asset { value, .. }, receiving_key) => {
+            let recipient = address_book.display(&address_to_base58(receiving_key));
+            let warning = if is_new_recipient {
+                " (first-time recipient)"
+            } else {
+                ""
+            };
+            match metadata.token_type {
+                TokenType::FT(decimals) => {
+                    format!(
+                        "Send {} to {} on {} network{}",
+                        metadata.display(*value, decimals),
+                        recipient,
+                        network,
+                        warning
+                    )
+                }
+                TokenType::NFT => {
+                    format!(
+                        "Send NFT to {} on {} network{}",
+                        recipient, network, warning
+                    )
+                }
+            }
+        }
+        Transaction::ToPublic(Asset { value, .. }, _) => match metadata.token_type {
+            TokenType::FT(decimals) => {
+                format!(
+                    "Public {} on {} network",
+                    metadata.display(*value, decimals),
+                    network
+                )
+            }
+            TokenType::NFT => {
+                format!("Public NFT on {} network", network)
+            }
+        },
+    }
+}
+
+/// Returns a summary of the current transaction when no asset metadata is available, using the
+/// raw asset id and value.
+#[inline]
+pub fn display_unknown_transaction(
+    transaction: &Transaction,
+    network: &NetworkId,
+    address_book: &AddressBook,
+    is_new_recipient: bool,
+) -> String {
+    match transaction {
+        Transaction::ToPrivate(Asset { id, value }) => format!(
+            "Privatize {} units of unknown asset {} on {} network",
+            value,
+            asset::display_id(id),
+            network
+        ),
+        Transaction::PrivateTransfer(Asset { id, value }, receiving_key) => format!(
+            "Send {} units of unknown asset {} to {} on {} network{}",
+            value,
+            asset::display_id(id),
+            address_book.display(&address_to_base58(receiving_key)),
+            network,
+            if is_new_recipient {
+                " (first-time recipient)"
+            } else {
+                ""
+            }
+        ),
+        Transaction::ToPublic(Asset { id, value }, _) => format!(
+            "Public {} units of unknown asset {} on {} network",
+            value,
+            asset::display_id(id),
+            network
+        ),
+    }
+}
+
+/// Authorization Recipient
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct Recipient {
+    /// Base58-Encoded Address
+    pub address: String,
+
+    /// Address Book Label
+    pub label: Option<String>,
+
+    /// First-Time Recipient Flag
+    ///
+    /// This flag is set if no transaction to this recipient was signed before.
+    pub is_new: bool,
+}
+
+/// Authorization Warning
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(
+    content = "content",
+    crate = "manta_util::serde",
+    deny_unknown_fields,
+    tag = "type"
+)]
+pub enum AuthorizationWarning {
+    /// Unknown Asset
+    ///
+    /// The sign request did not carry any asset metadata, so the value cannot be formatted and the
+    /// asset cannot be identified by name.
+    UnknownAsset {
+        /// Asset Id
+        asset_id: String,
+
+        /// Raw Asset Value
+        value: String,
+    },
+
+    /// Metadata Mismatch
+    ///
+    /// The asset metadata in the sign request does not match the asset registered in the signer,
+    /// so the displayed value and symbol may be wrong.
+    MetadataMismatch {
+        /// Asset Id
+        asset_id: String,
+
+        /// Registered Asset
+        registered: RegisteredAsset,
+    },
+
+    /// Unregistered Asset
+    ///
+    /// The asset is not in the signer asset registry, so its metadata comes from the requesting
+    /// site alone.
+    UnregisteredAsset {
+        /// Asset Id
+        asset_id: String,
+
+        /// Symbol Claimed by the Requesting Site
+        symbol: String,
+    },
+
+    /// Symbol Collision
+    ///
+    /// The asset is not in the signer asset registry but its metadata claims the symbol of a
+    /// registered asset, so it may be disguised as that asset.
+    SymbolCollision {
+        /// Asset Id
+        asset_id: String,
+
+        /// Registered Asset whose Symbol is Claimed
+        registered: RegisteredAsset,
+    },
+}
+
+impl AuthorizationWarning {
+    /// Returns the human-readable message for `self`.
+    #[inline]
+    pub fn message(&self) -> String {
+        match self {
+            Self::UnknownAsset { asset_id, value } => format!(
+                "Unknown asset: the requesting site did not describe asset {asset_id}. The raw \
+                 value being sent is {value}."
+            ),
+            Self::MetadataMismatch {
+                asset_id,
+                registered,
+            } => match registered.token_type {
+                RegisteredType::Fungible(decimals) => format!(
+                    "Metadata mismatch: asset {asset_id} is registered as {} with {decimals} \
+                     decimals, but the requesting site described it differently.",
+                    registered.symbol,
+                ),
+                _ => format!(
+                    "Metadata mismatch: asset {asset_id} is registered as {}, but the requesting \
+                     site described it differently.",
+                    registered.symbol,
+                ),
+            },
+            Self::UnregisteredAsset { asset_id, symbol } => format!(
+                "Unregistered asset: asset {asset_id} is not in the signer asset registry, so the \
+                 requesting site's description of it as {symbol} cannot be checked."
+            ),
+            Self::SymbolCollision {
+                asset_id,
+                registered,
+            } => format!(
+                "Impersonated asset: the requesting site describes asset {asset_id} as {}, which is \
+                 the symbol of the registered asset {}.",
+                registered.symbol, registered.id,
+            ),
+        }
+    }
+}
+
+/// Authorization Request
+///
+/// This is the prompt sent to the [`Authorizer`](crate::secret::Authorizer) when a transaction
+/// needs the user's approval. It carries both the raw transaction data, so that authorizers can
+/// render and validate the prompt themselves, and a preformatted human-readable `summary`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct AuthorizationRequest {
+    /// Request Id
+    ///
+    /// Identifier of this request, unique for the lifetime of the signer server.
+    pub request_id: u64,
+
+    /// Network
+    pub network: NetworkId,
+
+    /// Transfer Shape
+    pub shape: TransferShape,
+
+    /// Asset Id
+    ///
+    /// The numeric asset id if it fits into a `u128`, or the hex encoding of the asset id field
+    /// element otherwise.
+    pub asset_id: String,
+
+    /// Raw Asset Value
+    ///
+    /// This value is sent as a decimal string since it may not fit into a JSON number.
+    pub value: String,
+
+    /// Asset Decimals
+    ///
+    /// This is `None` for non-fungible assets or if no asset metadata was provided.
+    pub decimals: Option<u32>,
+
+    /// Asset Symbol
+    pub symbol: Option<String>,
+
+    /// Recipient
+    ///
+    /// Only private transfers have a recipient inside the shielded pool.
+    pub recipient: Option<Recipient>,
+
+    /// Requesting Origin
+    pub origin: Option<String>,
+
+    /// Human-Readable Summary
+    pub summary: String,
+
+    /// Warnings
+    ///
+    /// Warnings which must be shown prominently to the user before they authorize the request.
+    pub warnings: Vec<AuthorizationWarning>,
+}
+
+impl AuthorizationRequest {
+    /// Builds a new [`AuthorizationRequest`] for `transaction` on `network` with the given
+    /// `request_id` and requesting `origin`, using `metadata` to format the asset and
+    /// `address_book` to label the recipient.
+    #[inline]
+    pub fn new(
+        request_id: u64,
+        network: NetworkId,
+        transaction: &Transaction,
+        metadata: Option<&AssetMetadata>,
+        address_book: &AddressBook,
+        is_new_recipient: bool,
+        origin: Option<String>,
+    ) -> Self {
+        let (Asset { id, value }, recipient) = match transaction {
+            Transaction::ToPrivate(asset) => (asset, None),
+            Transaction::PrivateTransfer(asset, address) => {
+                let address = address_to_base58(address);
+                (
+                    asset,
+                    Some(Recipient {
+                        label: address_book.label(&address).map(ToOwned::to_owned),
+                        address,
+                        is_new: is_new_recipient,
+                    }),
+                )
+            }
+            Transaction::ToPublic(asset, _) => (asset, None),
+        };
+        let summary = match metadata {
+            Some(metadata) => display_transaction(
+                transaction,
+                metadata,
+                &network,
+                address_book,
+                is_new_recipient,
+            ),
+            _ => display_unknown_transaction(transaction, &network, address_book, is_new_recipient),
+        };
+        let asset_id = asset::display_id(id);
+        let value = value.to_string();
+        let mut warnings = Vec::new();
+        if metadata.is_none() {
+            warnings.push(AuthorizationWarning::UnknownAsset {
+                asset_id: asset_id.clone(),
+                value: value.clone(),
+            });
+        }
+        Self {
+            request_id,
+            network,
+            shape: transaction.shape(),
+            asset_id,
+            value,
+            decimals: metadata.and_then(|metadata| match metadata.token_type {
+                TokenType::FT(decimals) => Some(decimals),
+                TokenType::NFT => None,
+            }),
+            symbol: metadata.map(|metadata| metadata.symbol.clone()),
+            recipient,
+            origin,
+            summary,
+            warnings,
+        }
+    }
+
+    /// Adds `warning` to the warnings of `self`.
+    #[inline]
+    pub fn with_warning(mut self, warning: AuthorizationWarning) -> Self {
+        self.warnings.push(warning);
+        self
+    }
+}
+
+/// Action
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub enum Action {
+    /// Discards the synchronization state of a signer so that it resynchronizes from genesis
+    ResetSyncState,
+}
+
+/// Action Request
+///
+/// This is the prompt sent to the [`Authorizer`](crate::secret::Authorizer) when an operation
+/// which does not sign a transaction needs the user's approval.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct ActionRequest {
+    /// Action
+    pub action: Action,
+
+    /// Network
+    pub network: NetworkId,
+
+    /// Human-Readable Summary
+    pub summary: String,
+}
+
+impl ActionRequest {
+    /// Builds a new [`ActionRequest`] for resetting the synchronization state on `network`.
+    #[inline]
+    pub fn reset_sync_state(network: NetworkId) -> Self {
+        Self {
+            summary: format!("Reset the synchronization state on {network} network"),
+            action: Action::ResetSyncState,
+            network,
+        }
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Portable Backups
+//!
+//! A backup file holds everything needed to restore a wallet on another machine, including the
+//! synchronized signer states so that the restored wallet does not have to resynchronize. Backups
+//! are encrypted under their own backup password, independently of the wallet password.
+
+use crate::{address_book::AddressBook, secret::Zeroizing, wallet::WalletMetadata};
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use core::fmt::{self, Display};
+use manta_crypto::rand::{OsRng, RngCore};
+use manta_pay::{key::Mnemonic, signer::base::SignerState};
+use manta_util::serde::{Deserialize, Serialize};
+use std::error;
+
+/// Backup File Magic Bytes
+pub const MAGIC: &[u8; 8] = b"MSBACKUP";
+
+/// Backup File Format Version
+///
+/// Incremented whenever the layout of the file or of [`Backup`] changes. Only backups with this
+/// version can be imported.
+pub const VERSION: u16 = 1;
+
+/// Salt Length
+const SALT_LENGTH: usize = 16;
+
+/// Nonce Length
+const NONCE_LENGTH: usize = 12;
+
+/// Header Length
+///
+/// The header holds the magic bytes, the little-endian format version and the key derivation
+/// salt. It is authenticated together with the ciphertext.
+const HEADER_LENGTH: usize = MAGIC.len() + 2 + SALT_LENGTH;
+
+/// Backup Error
+#[derive(Debug)]
+pub enum BackupError {
+    /// Malformed Backup Error
+    ///
+    /// The file is not a backup file or has been truncated.
+    Malformed,
+
+    /// Unsupported Version Error
+    ///
+    /// The backup was written with a format version this signer cannot read.
+    UnsupportedVersion(u16),
+
+    /// Decryption Error
+    ///
+    /// The backup password is wrong or the backup has been tampered with.
+    Decryption,
+
+    /// Key Derivation Error
+    KeyDerivation,
+
+    /// Encoding Error
+    ///
+    /// The contents of the backup could not be encoded or decoded.
+    Encoding,
+}
+
+impl Display for BackupError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Malformed => write!(f, "the file is not a valid backup"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "backup format version {version} is not supported")
+            }
+            Self::Decryption => {
+                write!(f, "the backup password is wrong or the backup is corrupted")
+            }
+            Self::KeyDerivation => write!(f, "the backup key could not be derived"),
+            Self::Encoding => write!(f, "the backup contents could not be encoded"),
+        }
+    }
+}
+
+impl error::Error for BackupError {}
+
+/// Wallet Backup
+#[derive(Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct Backup {
+    /// Recovery Phrase
+    pub mnemonic: Mnemonic,
+
+    /// Wallet Metadata
+    ///
+    /// Holds the optional BIP39 passphrase, and the account labels.
+    pub wallet: WalletMetadata,
+
+    /// Address Book
+    pub address_book: AddressBook,
+
+    /// Signer States keyed by Network Name
+    ///
+    /// Each signer state carries its synchronization checkpoint, so restoring it continues
+    /// synchronization from where the backup was taken.
+    pub states: Vec<(String, SignerState)>,
+}
+
+impl Backup {
+    /// Encrypts `self` under `password`, returning the contents of the backup file.
+    #[inline]
+    pub fn encrypt(&self, password: &[u8]) -> Result<Vec<u8>, BackupError> {
+        let plaintext =
+            Zeroizing::new(bincode::serialize(self).map_err(|_| BackupError::Encoding)?);
+        let mut salt = [0; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce = [0; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let mut file = Vec::with_capacity(HEADER_LENGTH + NONCE_LENGTH + plaintext.len() + 16);
+        file.extend_from_slice(MAGIC);
+        file.extend_from_slice(&VERSION.to_le_bytes());
+        file.extend_from_slice(&salt);
+        let ciphertext = cipher(password, &salt)?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext.as_slice(),
+                    aad: &file,
+                },
+            )
+            .map_err(|_| BackupError::Encoding)?;
+        file.extend_from_slice(&nonce);
+        file.extend_from_slice(&ciphertext);
+        Ok(file)
+    }
+
+    /// Decrypts the backup file contents in `file` with `password`.
+    #[inline]
+    pub fn decrypt(file: &[u8], password: &[u8]) -> Result<Self, BackupError> {
+        if file.len() < HEADER_LENGTH + NONCE_LENGTH || !file.starts_with(MAGIC) {
+            return Err(BackupError::Malformed);
+        }
+        let (header, body) = file.split_at(HEADER_LENGTH);
+        let version = u16::from_le_bytes([header[MAGIC.len()], header[MAGIC.len() + 1]]);
+        if version != VERSION {
+            return Err(BackupError::UnsupportedVersion(version));
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_LENGTH);
+        let plaintext = Zeroizing::new(
+            cipher(password, &header[MAGIC.len() + 2..])?
+                .decrypt(
+                    Nonce::from_slice(nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: header,
+                    },
+                )
+                .map_err(|_| BackupError::Decryption)?,
+        );
+        bincode::deserialize(&plaintext).map_err(|_| BackupError::Encoding)
+    }
+}
+
+/// Derives the backup cipher from `password` and `salt` with Argon2.
+///
+/// The derived key is zeroized once the cipher is built, like the plaintext of the backup.
+#[inline]
+fn cipher(password: &[u8], salt: &[u8]) -> Result<ChaCha20Poly1305, BackupError> {
+    let mut key = Zeroizing::new([0; 32]);
+    Argon2::default()
+        .hash_password_into(password, salt, key.as_mut())
+        .map_err(|_| BackupError::KeyDerivation)?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(key.as_ref())))
+}
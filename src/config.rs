@@ -16,12 +16,17 @@
 
 //! Manta Signer Configuration
 
+use crate::{
+    network::{is_valid_custom_name, CustomNetwork, NetworkId},
+    secret::{empty_secret, SecretString},
+};
 use manta_pay::{
     key::Mnemonic,
     signer::client::network::{Network, NetworkSpecific},
 };
 use manta_util::serde::{Deserialize, Serialize};
 use std::{
+    collections::HashSet,
     io,
     path::{Path, PathBuf},
 };
@@ -30,30 +35,254 @@ use tokio::fs;
 /// Manta Path Identifier
 pub const PATH_IDENTIFIER: &str = "manta-signer";
 
-/// Pushes the [`PATH_IDENTIFIER`] to the end of the given `path` if it exists, attaching the file
-/// `name` afterwards.
+/// Networks Supported by the Signer
+pub const NETWORKS: [Network; 3] = [Network::Dolphin, Network::Calamari, Network::Manta];
+
+/// Default Profile Name
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Profiles Directory Name
+pub const PROFILES_DIRECTORY: &str = "profiles";
+
+/// Selected Profile File Name
+pub const SELECTED_PROFILE_FILE: &str = "profile";
+
+/// Settings File Name
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Proving Directory Environment Variable
+///
+/// Overrides the proving directory of every [`Config`] when it is set.
+pub const PROVING_DIRECTORY_VARIABLE: &str = "MANTA_SIGNER_PROVING_DIRECTORY";
+
+/// Returns the root directory of the signer, which is the [`PATH_IDENTIFIER`] directory inside
+/// the user configuration directory.
 #[inline]
-fn file<P>(path: Option<PathBuf>, name: P) -> Option<PathBuf>
-where
-    P: AsRef<Path>,
-{
-    path.map(move |mut p| {
-        p.push(PATH_IDENTIFIER);
-        p.push(name);
-        p
+pub fn root_directory() -> Option<PathBuf> {
+    dirs_next::config_dir().map(|mut path| {
+        path.push(PATH_IDENTIFIER);
+        path
     })
 }
 
+/// Returns `true` if `name` is a valid profile name. Profile names are used as directory names
+/// so they are restricted to ASCII letters, digits, `-` and `_`.
+#[inline]
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Returns the data directory of `profile` inside the signer `root` directory.
+///
+/// The default profile lives directly in the `root` directory so that installations from before
+/// profiles were introduced keep working.
+#[inline]
+pub fn profile_directory(root: &Path, profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        root.to_owned()
+    } else {
+        root.join(PROFILES_DIRECTORY).join(profile)
+    }
+}
+
+/// Returns the proving directory set in the [`PROVING_DIRECTORY_VARIABLE`] environment variable.
+#[inline]
+pub fn proving_directory_override() -> Option<PathBuf> {
+    std::env::var_os(PROVING_DIRECTORY_VARIABLE)
+        .filter(|directory| !directory.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Reads the name of the selected profile from the signer `root` directory, falling back to the
+/// [`DEFAULT_PROFILE`].
+#[inline]
+pub fn selected_profile(root: &Path) -> String {
+    std::fs::read_to_string(root.join(SELECTED_PROFILE_FILE))
+        .ok()
+        .map(|profile| profile.trim().to_owned())
+        .filter(|profile| is_valid_profile_name(profile))
+        .unwrap_or_else(|| DEFAULT_PROFILE.into())
+}
+
+/// Settings
+///
+/// User settings shared by all the profiles, which are read from the [`SETTINGS_FILE`] in the
+/// signer root directory. Missing fields take their default values.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", default, deny_unknown_fields)]
+pub struct Settings {
+    /// Enabled Networks
+    pub enabled_networks: Vec<Network>,
+
+    /// Custom Networks
+    pub custom_networks: Vec<CustomNetwork>,
+
+    /// Read Authorization Flag
+    pub require_read_authorization: bool,
+
+    /// Missing Metadata Policy
+    pub missing_metadata_policy: AssetPolicy,
+
+    /// Metadata Mismatch Policy
+    pub metadata_mismatch_policy: AssetPolicy,
+}
+
+impl Settings {
+    /// Reads the settings from `path`, writing the default settings to `path` if the file does
+    /// not exist yet so that they can be edited.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidData`](io::ErrorKind::InvalidData) error if the file cannot be decoded
+    /// or fails [`validate`](Self::validate).
+    #[inline]
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let settings = match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice::<Self>(&bytes)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                let settings = Self::default();
+                if let Some(directory) = path.parent() {
+                    let _ = std::fs::create_dir_all(directory);
+                }
+                let _ = std::fs::write(path, serde_json::to_vec_pretty(&settings)?);
+                return Ok(settings);
+            }
+            Err(err) => return Err(err),
+        };
+        settings.validate().map_err(|message| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid settings in {}: {message}.", path.display()),
+            )
+        })?;
+        Ok(settings)
+    }
+
+    /// Checks that at least one network is enabled, that no network is listed twice and that the
+    /// custom network names are valid, returning the reason if they are not.
+    #[inline]
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled_networks.is_empty() && self.custom_networks.is_empty() {
+            return Err("no network is enabled".into());
+        }
+        let mut enabled = HashSet::new();
+        for network in &self.enabled_networks {
+            if !enabled.insert(network) {
+                return Err(format!("network {network} is enabled twice"));
+            }
+        }
+        let mut custom = HashSet::new();
+        for network in &self.custom_networks {
+            if !is_valid_custom_name(&network.name) {
+                return Err(format!(
+                    "{:?} is not a valid custom network name",
+                    network.name
+                ));
+            }
+            if !custom.insert(&network.name) {
+                return Err(format!(
+                    "custom network {} is configured twice",
+                    network.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Settings {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            enabled_networks: NETWORKS.to_vec(),
+            custom_networks: vec![],
+            require_read_authorization: false,
+            missing_metadata_policy: AssetPolicy::Warn,
+            metadata_mismatch_policy: AssetPolicy::Reject,
+        }
+    }
+}
+
+/// Returns the per-network file paths `{prefix}-{network}.{extension}` inside `directory`.
+#[inline]
+fn network_files(directory: &Path, prefix: &str, extension: &str) -> NetworkSpecific<PathBuf> {
+    NetworkSpecific {
+        dolphin: directory.join(format!("{prefix}-dolphin.{extension}")),
+        calamari: directory.join(format!("{prefix}-calamari.{extension}")),
+        manta: directory.join(format!("{prefix}-manta.{extension}")),
+    }
+}
+
 /// Configuration
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 #[serde(crate = "manta_util::serde", deny_unknown_fields)]
 pub struct Config {
+    /// Signer Root Directory
+    ///
+    /// Directory holding the profile directories and the selected profile file.
+    pub root_directory: PathBuf,
+
+    /// Profile Name
+    pub profile: String,
+
     /// Data File Path
     pub data_path: NetworkSpecific<PathBuf>,
 
     /// Backup File Path
     pub backup_data_path: NetworkSpecific<PathBuf>,
 
+    /// Transaction History File Path
+    pub history_path: NetworkSpecific<PathBuf>,
+
+    /// Wallet Metadata File Path
+    pub wallet_path: PathBuf,
+
+    /// Address Book File Path
+    pub address_book_path: PathBuf,
+
+    /// Custom Asset Registry File Path
+    pub asset_registry_path: PathBuf,
+
+    /// Key Derivation Header File Path
+    ///
+    /// Holds the Argon2 parameters and the salt which the key of the encrypted files in the
+    /// profile directory is derived with.
+    pub kdf_header_path: PathBuf,
+
+    /// Password Attempts File Path
+    ///
+    /// Keeps track of failed password attempts across restarts of the signer.
+    pub password_attempts_path: PathBuf,
+
+    /// Proving Directory
+    ///
+    /// Directory holding the proving contexts shipped with the signer. They are checked against
+    /// the checksums embedded in the signer before they are used.
+    pub proving_directory: PathBuf,
+
+    /// Proving Context Cache Directory
+    ///
+    /// Directory holding pre-processed copies of the proving contexts, keyed by the checksum of
+    /// their source file, which decode much faster than the source files. The cache is disabled
+    /// if this is not set.
+    pub proving_cache_directory: Option<PathBuf>,
+
+    /// Enabled Networks
+    ///
+    /// Signer states are only loaded for these networks, on their first request, and requests for
+    /// any other network are rejected.
+    pub enabled_networks: Vec<Network>,
+
+    /// Custom Networks
+    ///
+    /// Networks beyond the built-in ones, like local development chains or staging forks. Each of
+    /// them is enabled and keeps its own data files inside the profile directory.
+    pub custom_networks: Vec<CustomNetwork>,
+
     /// Service URL
     ///
     /// This URL defines the listening URL for the service.
@@ -70,6 +299,37 @@ pub struct Config {
     /// normal builds, not dev mode.
     /// Thus when running `cargo dev` the feature `disable-restart` must be enabled.
     pub can_app_restart: bool,
+
+    /// Read Authorization Flag
+    ///
+    /// If this flag is set, read-only queries which expose private wallet information, like the
+    /// balance query, require the user to authorize them with their password.
+    pub require_read_authorization: bool,
+
+    /// Missing Metadata Policy
+    ///
+    /// Policy for private transfers and to-public transactions which are sent without asset
+    /// metadata. To-private transactions never require metadata.
+    pub missing_metadata_policy: AssetPolicy,
+
+    /// Metadata Mismatch Policy
+    ///
+    /// Policy for sign requests whose asset metadata does not match the signer asset registry,
+    /// including metadata which claims the symbol of a registered asset for an unregistered one.
+    pub metadata_mismatch_policy: AssetPolicy,
+}
+
+/// Asset Policy
+///
+/// Describes how the signer treats sign requests whose asset information cannot be trusted.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub enum AssetPolicy {
+    /// Rejects the request without asking the user.
+    Reject,
+
+    /// Asks the user for authorization, showing them a prominent warning.
+    Warn,
 }
 
 /// Response for the [`Config::does_data_exist`] function of [`Config`]. The boolean fields
@@ -77,20 +337,63 @@ pub struct Config {
 pub type DataExistenceResponse = NetworkSpecific<bool>;
 
 impl Config {
-    /// Tries to build a default [`Config`].
+    /// Loads the [`Config`] of the selected profile, applying the [`Settings`] in the signer root
+    /// directory.
+    #[inline]
+    pub fn load() -> io::Result<Self> {
+        let root_directory = root_directory().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "Unable to find the user configuration directory.",
+            )
+        })?;
+        let settings = Settings::load(&root_directory.join(SETTINGS_FILE))?;
+        let profile = selected_profile(&root_directory);
+        Ok(Self::in_profile(root_directory, profile).with_settings(settings))
+    }
+
+    /// Tries to load the [`Config`] of the selected profile, returning `None` if the settings
+    /// cannot be read. See [`Config::load`] for the error.
     #[inline]
     pub fn try_default() -> Option<Self> {
-        Some(Self {
-            data_path: NetworkSpecific {
-                dolphin: file(dirs_next::config_dir(), "storage-dolphin.dat")?,
-                calamari: file(dirs_next::config_dir(), "storage-calamari.dat")?,
-                manta: file(dirs_next::config_dir(), "storage-manta.dat")?,
-            },
-            backup_data_path: NetworkSpecific {
-                dolphin: file(dirs_next::config_dir(), "storage-dolphin.backup")?,
-                calamari: file(dirs_next::config_dir(), "storage-calamari.backup")?,
-                manta: file(dirs_next::config_dir(), "storage-manta.backup")?,
-            },
+        Self::load().ok()
+    }
+
+    /// Returns a copy of `self` with the user `settings` applied.
+    #[inline]
+    pub fn with_settings(self, settings: Settings) -> Self {
+        Self {
+            enabled_networks: settings.enabled_networks,
+            custom_networks: settings.custom_networks,
+            require_read_authorization: settings.require_read_authorization,
+            missing_metadata_policy: settings.missing_metadata_policy,
+            metadata_mismatch_policy: settings.metadata_mismatch_policy,
+            ..self
+        }
+    }
+
+    /// Builds a [`Config`] for `profile` inside the signer `root_directory` with the default
+    /// [`Settings`].
+    #[inline]
+    fn in_profile(root_directory: PathBuf, profile: String) -> Self {
+        let directory = profile_directory(&root_directory, &profile);
+        let settings = Settings::default();
+        Self {
+            data_path: network_files(&directory, "storage", "dat"),
+            backup_data_path: network_files(&directory, "storage", "backup"),
+            history_path: network_files(&directory, "history", "dat"),
+            wallet_path: directory.join("wallet.dat"),
+            address_book_path: directory.join("address-book.dat"),
+            asset_registry_path: directory.join("asset-registry.json"),
+            kdf_header_path: directory.join("kdf-header.json"),
+            password_attempts_path: directory.join("password-attempts.json"),
+            proving_directory: proving_directory_override()
+                .unwrap_or_else(|| root_directory.join("proving")),
+            proving_cache_directory: Some(root_directory.join("proving-cache")),
+            root_directory,
+            profile,
+            enabled_networks: settings.enabled_networks,
+            custom_networks: settings.custom_networks,
             service_url: "127.0.0.1:29987".into(),
             #[cfg(feature = "unsafe-disable-cors")]
             origin_urls: vec![],
@@ -103,9 +406,175 @@ impl Config {
             can_app_restart: false,
             #[cfg(not(feature = "disable-restart"))]
             can_app_restart: true,
+            require_read_authorization: settings.require_read_authorization,
+            missing_metadata_policy: settings.missing_metadata_policy,
+            metadata_mismatch_policy: settings.metadata_mismatch_policy,
+        }
+    }
+
+    /// Returns a copy of `self` whose data files are those of `profile`, keeping all the other
+    /// settings. Returns `None` if `profile` is not a valid profile name.
+    #[inline]
+    pub fn with_profile(&self, profile: &str) -> Option<Self> {
+        if !is_valid_profile_name(profile) {
+            return None;
+        }
+        let paths = Self::in_profile(self.root_directory.clone(), profile.into());
+        Some(Self {
+            profile: paths.profile,
+            data_path: paths.data_path,
+            backup_data_path: paths.backup_data_path,
+            history_path: paths.history_path,
+            wallet_path: paths.wallet_path,
+            address_book_path: paths.address_book_path,
+            asset_registry_path: paths.asset_registry_path,
+            kdf_header_path: paths.kdf_header_path,
+            password_attempts_path: paths.password_attempts_path,
+            ..self.clone()
         })
     }
 
+    /// Uses the proving contexts bundled with the signer in `directory`, unless the
+    /// [`PROVING_DIRECTORY_VARIABLE`] environment variable overrides them.
+    #[inline]
+    pub fn use_bundled_proving_directory(&mut self, directory: PathBuf) {
+        if proving_directory_override().is_none() {
+            self.proving_directory = directory;
+        }
+    }
+
+    /// Returns the names of all the profiles in the signer root directory. The default profile is
+    /// always listed first.
+    #[inline]
+    pub async fn profiles(&self) -> io::Result<Vec<String>> {
+        let mut profiles = vec![DEFAULT_PROFILE.to_owned()];
+        let mut entries = match fs::read_dir(self.root_directory.join(PROFILES_DIRECTORY)).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(profiles),
+            Err(err) => return Err(err),
+        };
+        let mut named = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                if let Some(name) = entry.file_name().to_str() {
+                    if is_valid_profile_name(name) && name != DEFAULT_PROFILE {
+                        named.push(name.to_owned());
+                    }
+                }
+            }
+        }
+        named.sort();
+        profiles.extend(named);
+        Ok(profiles)
+    }
+
+    /// Persists the profile of `self` as the selected profile, so that the next
+    /// [`Config::try_default`] picks it up.
+    #[inline]
+    pub async fn save_selected_profile(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.root_directory).await?;
+        fs::write(
+            self.root_directory.join(SELECTED_PROFILE_FILE),
+            self.profile.as_bytes(),
+        )
+        .await
+    }
+
+    /// Returns `true` if `network` is one of the enabled built-in networks or a configured custom
+    /// network.
+    #[inline]
+    pub fn is_enabled(&self, network: &NetworkId) -> bool {
+        match network {
+            NetworkId::Builtin(network) => self.enabled_networks.contains(network),
+            NetworkId::Custom(name) => self.custom_network(name).is_some(),
+        }
+    }
+
+    /// Returns the custom network called `name` if it is configured.
+    #[inline]
+    pub fn custom_network(&self, name: &str) -> Option<&CustomNetwork> {
+        self.custom_networks
+            .iter()
+            .find(|network| network.name == name)
+    }
+
+    /// Returns all the networks known to the signer, built-in networks first.
+    #[inline]
+    pub fn networks(&self) -> Vec<NetworkId> {
+        NETWORKS
+            .into_iter()
+            .map(NetworkId::from)
+            .chain(self.custom_networks.iter().map(CustomNetwork::id))
+            .collect()
+    }
+
+    /// Returns all the enabled networks, built-in networks first.
+    #[inline]
+    pub fn enabled(&self) -> Vec<NetworkId> {
+        self.enabled_networks
+            .iter()
+            .copied()
+            .map(NetworkId::from)
+            .chain(self.custom_networks.iter().map(CustomNetwork::id))
+            .collect()
+    }
+
+    /// Returns the path of the file of `network` with the given `prefix` and `extension`, taking
+    /// built-in network files from `builtin`.
+    #[inline]
+    fn network_file(
+        &self,
+        network: &NetworkId,
+        builtin: &NetworkSpecific<PathBuf>,
+        prefix: &str,
+        extension: &str,
+    ) -> PathBuf {
+        match network {
+            NetworkId::Builtin(network) => builtin[*network].clone(),
+            NetworkId::Custom(name) => self
+                .data_directory()
+                .join(format!("{prefix}-{name}.{extension}")),
+        }
+    }
+
+    /// Returns the data file path of `network`.
+    #[inline]
+    pub fn data_file(&self, network: &NetworkId) -> PathBuf {
+        self.network_file(network, &self.data_path, "storage", "dat")
+    }
+
+    /// Returns the backup file path of `network`.
+    #[inline]
+    pub fn backup_data_file(&self, network: &NetworkId) -> PathBuf {
+        self.network_file(network, &self.backup_data_path, "storage", "backup")
+    }
+
+    /// Returns the transaction history file path of `network`.
+    #[inline]
+    pub fn history_file(&self, network: &NetworkId) -> PathBuf {
+        self.network_file(network, &self.history_path, "history", "dat")
+    }
+
+    /// Returns the paths of the files in the profile directory which are encrypted under the
+    /// password key, whether they exist or not.
+    #[inline]
+    pub fn encrypted_files(&self) -> Vec<PathBuf> {
+        let mut files = self
+            .networks()
+            .into_iter()
+            .flat_map(|network| {
+                [
+                    self.data_file(&network),
+                    self.backup_data_file(&network),
+                    self.history_file(&network),
+                ]
+            })
+            .collect::<Vec<_>>();
+        files.push(self.wallet_path.clone());
+        files.push(self.address_book_path.clone());
+        files
+    }
+
     /// Returns the data directory path. All files will be in same directory so it suffices to check
     /// on one file i.e. Dolphin.
     #[inline]
@@ -154,21 +623,19 @@ impl Config {
     /// Checks for existence of backup storage file for a particular network.
     /// If found, will set the backup to be the default storage file.
     #[inline]
-    pub async fn check_for_backup(&self, network: Network) -> io::Result<bool> {
+    pub async fn check_for_backup(&self, network: &NetworkId) -> io::Result<bool> {
         fs::create_dir_all(self.data_directory()).await?;
-        match fs::metadata(&self.backup_data_path[network]).await {
+        let data_file = self.data_file(network);
+        let backup_data_file = self.backup_data_file(network);
+        match fs::metadata(&backup_data_file).await {
             Ok(metadata) if metadata.is_file() => {
                 // need to check if old storage file still exists before deleting.
-                if let Ok(metadata) = fs::metadata(&self.data_path[network]).await {
+                if let Ok(metadata) = fs::metadata(&data_file).await {
                     if metadata.is_file() {
-                        fs::remove_file(self.data_path[network].clone()).await?;
+                        fs::remove_file(&data_file).await?;
                     }
                 }
-                fs::rename(
-                    self.backup_data_path[network].clone(),
-                    self.data_path[network].clone(),
-                )
-                .await?;
+                fs::rename(backup_data_file, data_file).await?;
                 Ok(true)
             }
             Ok(metadata) => Err(io::Error::new(
@@ -183,20 +650,12 @@ impl Config {
     /// using the backup.
     #[inline]
     pub async fn check_all_backups(&self) -> io::Result<bool> {
-        let dolphin_backup_exists = self
-            .check_for_backup(Network::Dolphin)
-            .await
-            .expect("unable to check for Dolphin backup");
-        let calamari_backup_exists = self
-            .check_for_backup(Network::Calamari)
-            .await
-            .expect("unable to check for Calamari backup");
-        let manta_backup_exists = self
-            .check_for_backup(Network::Manta)
-            .await
-            .expect("unable to check for Manta backup");
-
-        Ok(dolphin_backup_exists && calamari_backup_exists && manta_backup_exists)
+        let mut all_backups_exist = true;
+        for network in self.networks() {
+            let backup_exists = self.check_for_backup(&network).await?;
+            all_backups_exist &= backup_exists;
+        }
+        Ok(all_backups_exist)
     }
 }
 
@@ -210,8 +669,40 @@ impl Config {
 )]
 pub enum Setup {
     /// Create Account
-    CreateAccount(Mnemonic),
+    CreateAccount {
+        /// Recovery Phrase
+        mnemonic: Mnemonic,
+
+        /// Optional BIP39 Passphrase
+        ///
+        /// The passphrase is never sent to the front end.
+        #[serde(skip)]
+        passphrase: Option<SecretString>,
+    },
+
+    /// Import Backup
+    ImportBackup {
+        /// Backup File Path
+        path: PathBuf,
+
+        /// Backup Password
+        ///
+        /// The backup password is never sent to the front end.
+        #[serde(skip, default = "empty_secret")]
+        password: SecretString,
+    },
 
     /// Login
     Login,
 }
+
+impl Setup {
+    /// Builds a new [`Setup::CreateAccount`] for `mnemonic` without a passphrase.
+    #[inline]
+    pub fn create_account(mnemonic: Mnemonic) -> Self {
+        Self::CreateAccount {
+            mnemonic,
+            passphrase: None,
+        }
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Transaction History
+//!
+//! Every transaction which reaches [`Server::sign`](crate::service::Server::sign) is recorded in
+//! an append-only history, one per network, which is encrypted under the same password key as the
+//! signer state.
+
+use crate::asset;
+use manta_accounting::{asset::Asset, transfer::canonical::TransferShape};
+use manta_pay::config::{address_to_base58, Address, AssetId, AssetValue, Transaction};
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Transaction Outcome
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub enum Outcome {
+    /// The transaction was signed and returned to the requesting origin.
+    Signed,
+
+    /// The user declined to authorize the transaction.
+    Declined,
+
+    /// The signer rejected the transaction before asking the user.
+    Rejected,
+
+    /// The signer failed to sign the transaction.
+    Failed,
+}
+
+/// History Entry
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct Entry {
+    /// Timestamp in RFC 3339 format
+    pub timestamp: String,
+
+    /// Transfer Shape
+    pub shape: TransferShape,
+
+    /// Asset Id
+    pub asset_id: AssetId,
+
+    /// Asset Value
+    pub value: AssetValue,
+
+    /// Recipient Address
+    ///
+    /// Only private transfers have a recipient inside the shielded pool.
+    pub recipient: Option<Address>,
+
+    /// Requesting Origin
+    pub origin: Option<String>,
+
+    /// Outcome
+    pub outcome: Outcome,
+}
+
+impl Entry {
+    /// Builds a new [`Entry`] for `transaction` requested by `origin` which ended with `outcome`,
+    /// timestamped with the current time.
+    #[inline]
+    pub fn new(transaction: &Transaction, origin: Option<String>, outcome: Outcome) -> Self {
+        let (Asset { id, value }, recipient) = match transaction {
+            Transaction::ToPrivate(asset) => (asset, None),
+            Transaction::PrivateTransfer(asset, address) => (asset, Some(address.clone())),
+            Transaction::ToPublic(asset, _) => (asset, None),
+        };
+        Self {
+            timestamp: chrono::offset::Utc::now().to_rfc3339(),
+            shape: transaction.shape(),
+            asset_id: *id,
+            value: *value,
+            recipient,
+            origin,
+            outcome,
+        }
+    }
+
+    /// Writes `self` as a CSV row into `output`.
+    #[inline]
+    fn write_csv(&self, output: &mut String) {
+        let fields = [
+            self.timestamp.clone(),
+            format!("{:?}", self.shape),
+            asset::display_id(&self.asset_id),
+            self.value.to_string(),
+            self.recipient
+                .as_ref()
+                .map(address_to_base58)
+                .unwrap_or_default(),
+            self.origin.clone().unwrap_or_default(),
+            format!("{:?}", self.outcome),
+        ];
+        for (i, field) in fields.iter().enumerate() {
+            if i != 0 {
+                output.push(',');
+            }
+            output.push_str(&csv_field(field));
+        }
+        output.push('\n');
+    }
+}
+
+/// Escapes `field` for inclusion in a CSV row, quoting it if it contains a delimiter.
+#[inline]
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// CSV Export Header
+pub const CSV_HEADER: &str = "timestamp,shape,asset_id,value,recipient,origin,outcome\n";
+
+/// Transaction History
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct History {
+    /// Entries in Chronological Order
+    entries: Vec<Entry>,
+}
+
+impl History {
+    /// Returns the number of entries in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if `self` has no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Appends `entry` to the end of the history.
+    #[inline]
+    pub fn push(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    /// Returns an iterator over the entries in `self` in chronological order.
+    #[inline]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    /// Returns `true` if a transaction to the base58-encoded `recipient` was signed before.
+    #[inline]
+    pub fn has_signed_for(&self, recipient: &str) -> bool {
+        self.entries.iter().any(|entry| {
+            entry.outcome == Outcome::Signed
+                && entry
+                    .recipient
+                    .as_ref()
+                    .map(|address| address_to_base58(address) == recipient)
+                    .unwrap_or(false)
+        })
+    }
+
+    /// Returns the page of entries described by `query`, newest first.
+    #[inline]
+    pub fn page(&self, query: HistoryQuery) -> HistoryPage {
+        HistoryPage {
+            entries: self
+                .entries
+                .iter()
+                .rev()
+                .skip(query.page.saturating_mul(query.page_size))
+                .take(query.page_size)
+                .cloned()
+                .collect(),
+            total: self.len(),
+        }
+    }
+
+    /// Exports the full history in the given `format`.
+    #[inline]
+    pub fn export(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Csv => {
+                let mut output = String::from(CSV_HEADER);
+                for entry in &self.entries {
+                    entry.write_csv(&mut output);
+                }
+                output
+            }
+            ExportFormat::Json => serde_json::to_string(&self.entries)
+                .expect("History entries are always serializable to JSON."),
+        }
+    }
+}
+
+/// History Query
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct HistoryQuery {
+    /// Page Index
+    pub page: usize,
+
+    /// Number of Entries per Page
+    pub page_size: usize,
+}
+
+/// History Page
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct HistoryPage {
+    /// Entries on this Page, Newest First
+    pub entries: Vec<Entry>,
+
+    /// Total Number of Entries in the History
+    pub total: usize,
+}
+
+/// History Export Format
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub enum ExportFormat {
+    /// Comma-Separated Values
+    Csv,
+
+    /// JSON Array of Entries
+    Json,
+}
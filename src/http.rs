@@ -48,6 +48,33 @@ where
     into_body(move || async move { f(request.state().clone(), args).await }).await
 }
 
+/// Returns the value of the `Origin` header of `request` if one was sent.
+#[inline]
+pub fn origin<S>(request: &Request<S>) -> Option<String> {
+    request
+        .header("Origin")
+        .map(|values| values.last().as_str().to_owned())
+}
+
+/// Executes `f` on the incoming `request`, also passing the [`origin`] of the request.
+#[inline]
+pub async fn execute_with_origin<S, T, R, E, F, Fut>(
+    mut request: Request<S>,
+    f: F,
+) -> Result<Response, Error>
+where
+    S: Clone,
+    T: DeserializeOwned,
+    R: Serialize,
+    E: Into<Error>,
+    F: FnOnce(S, T, Option<String>) -> Fut,
+    Fut: Future<Output = Result<R, E>>,
+{
+    let args = request.body_json::<T>().await?;
+    let origin = origin(&request);
+    into_body(move || async move { f(request.state().clone(), args, origin).await }).await
+}
+
 /// Registers a `POST` command with the given `path` and execution `f`.
 #[inline]
 pub fn register_post<S, T, R, E, F, Fut>(api: &mut Server<S>, path: &'static str, f: F)
@@ -61,3 +88,19 @@ where
 {
     api.at(path).post(move |r| execute(r, f.clone()));
 }
+
+/// Registers a `POST` command with the given `path` and execution `f`, where `f` also receives
+/// the [`origin`] of the request.
+#[inline]
+pub fn register_post_with_origin<S, T, R, E, F, Fut>(api: &mut Server<S>, path: &'static str, f: F)
+where
+    S: Clone + Send + Sync + 'static,
+    T: DeserializeOwned + Send + 'static,
+    R: Serialize + 'static,
+    E: Into<Error> + 'static,
+    F: Clone + Send + Sync + 'static + Fn(S, T, Option<String>) -> Fut,
+    Fut: Future<Output = Result<R, E>> + Send + 'static,
+{
+    api.at(path)
+        .post(move |r| execute_with_origin(r, f.clone()));
+}
@@ -0,0 +1,351 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Key Derivation Parameters
+//!
+//! The encrypted files of a profile are written in the file format of `manta-accounting`, which
+//! has no room for extra metadata, so the Argon2 parameters and the salt their key is derived with
+//! are kept in a header file next to them. The parameters are calibrated on the machine where the
+//! account is created, and profiles without a header file use the parameters of
+//! [`Argon2::default`] with the fixed salt of earlier signer versions.
+
+use crate::secret::{Argon2, PasswordHasher};
+use argon2::{Algorithm, Params, Version};
+use core::time::Duration;
+use manta_crypto::rand::{OsRng, RngCore};
+use manta_util::serde::{Deserialize, Serialize};
+use password_hash::SaltString;
+use std::{
+    ffi::OsString,
+    io,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+use tokio::fs;
+
+/// Key Derivation Header Format Version
+pub const VERSION: u16 = 1;
+
+/// Minimum Memory Cost in KiB
+///
+/// Follows the second recommended option of RFC 9106, which uses 64 MiB of memory.
+pub const MIN_MEMORY_COST: u32 = 64 * 1024;
+
+/// Minimum Number of Passes
+pub const MIN_TIME_COST: u32 = 3;
+
+/// Maximum Number of Passes
+///
+/// Bounds the calibration so that a slow measurement cannot lock the user out of a faster
+/// machine.
+pub const MAX_TIME_COST: u32 = 64;
+
+/// Calibration Target Duration
+pub const TARGET_DURATION: Duration = Duration::from_millis(500);
+
+/// Legacy Salt
+///
+/// Salt used by signer versions which did not write a header file.
+const LEGACY_SALT: &[u8] = b"default password salt";
+
+/// Salt Length
+const SALT_LENGTH: usize = 16;
+
+/// Pending File Extension
+const PENDING_EXTENSION: &str = ".rekey";
+
+/// Key Derivation Header
+///
+/// The default header is the legacy header, which matches the key derivation of signer versions
+/// without a header file.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct KdfHeader {
+    /// Format Version
+    pub version: u16,
+
+    /// Memory Cost in KiB
+    pub memory_cost: u32,
+
+    /// Number of Passes
+    pub time_cost: u32,
+
+    /// Degree of Parallelism
+    pub parallelism: u32,
+
+    /// B64-Encoded Salt
+    pub salt: String,
+}
+
+impl KdfHeader {
+    /// Builds a new [`KdfHeader`] with the given costs and a random salt.
+    #[inline]
+    fn new(memory_cost: u32, time_cost: u32) -> Self {
+        let mut salt = [0; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            version: VERSION,
+            memory_cost,
+            time_cost,
+            parallelism: 1,
+            salt: SaltString::b64_encode(&salt)
+                .expect("The salt length is within the allowed bounds.")
+                .as_str()
+                .into(),
+        }
+    }
+
+    /// Builds a new [`KdfHeader`] with a random salt whose number of passes makes deriving a key
+    /// take about [`TARGET_DURATION`] on this machine.
+    ///
+    /// # Implementation Note
+    ///
+    /// This function derives a key to measure it, so it blocks for about the minimum cost and
+    /// should not be called on an asynchronous task.
+    #[inline]
+    pub fn calibrate() -> Self {
+        let mut header = Self::new(MIN_MEMORY_COST, MIN_TIME_COST);
+        let started = Instant::now();
+        let _ = header
+            .hasher()
+            .hash_password(b"calibration password", &header.salt_string());
+        let elapsed = started.elapsed().max(Duration::from_millis(1));
+        let time_cost = (f64::from(MIN_TIME_COST) * TARGET_DURATION.as_secs_f64()
+            / elapsed.as_secs_f64())
+        .ceil() as u32;
+        header.time_cost = time_cost.clamp(MIN_TIME_COST, MAX_TIME_COST);
+        header
+    }
+
+    /// Returns `true` if the costs of `self` are below the recommended minimum, in which case the
+    /// files should be re-keyed.
+    #[inline]
+    pub fn is_weak(&self) -> bool {
+        self.memory_cost < MIN_MEMORY_COST || self.time_cost < MIN_TIME_COST
+    }
+
+    /// Returns the Argon2 parameters of `self`, if they are valid.
+    #[inline]
+    fn params(&self) -> Option<Params> {
+        Params::new(self.memory_cost, self.time_cost, self.parallelism, None).ok()
+    }
+
+    /// Returns the Argon2 hasher for the parameters of `self`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the parameters are invalid, which [`load`](Self::load) and the
+    /// constructors of [`KdfHeader`] rule out.
+    #[inline]
+    pub fn hasher(&self) -> Argon2 {
+        Argon2::new(
+            Algorithm::Argon2id,
+            Version::V0x13,
+            self.params()
+                .expect("The key derivation parameters are checked when they are loaded."),
+        )
+    }
+
+    /// Returns the salt of `self`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the salt is invalid, which [`load`](Self::load) and the constructors
+    /// of [`KdfHeader`] rule out.
+    #[inline]
+    pub fn salt_string(&self) -> SaltString {
+        SaltString::new(&self.salt).expect("The salt is checked when it is loaded.")
+    }
+
+    /// Loads the header from `path`, returning the legacy header if the file does not exist.
+    #[inline]
+    pub async fn load<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let header = match fs::read(path).await {
+            Ok(bytes) => serde_json::from_slice::<Self>(&bytes)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err),
+        };
+        if header.version != VERSION
+            || header.params().is_none()
+            || SaltString::new(&header.salt).is_err()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid key derivation header",
+            ));
+        }
+        Ok(header)
+    }
+
+    /// Saves the header to `path`.
+    #[inline]
+    pub async fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, serde_json::to_vec_pretty(self)?).await
+    }
+}
+
+impl Default for KdfHeader {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            memory_cost: Params::DEFAULT_M_COST,
+            time_cost: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+            salt: SaltString::b64_encode(LEGACY_SALT)
+                .expect("The legacy salt length is within the allowed bounds.")
+                .as_str()
+                .into(),
+        }
+    }
+}
+
+/// Returns the path that the re-keyed or restored contents of `path` are written to before they
+/// replace it. An empty file at this path marks `path` for removal instead.
+#[inline]
+pub fn pending_path(path: &Path) -> PathBuf {
+    let mut pending = OsString::from(path.as_os_str());
+    pending.push(PENDING_EXTENSION);
+    pending.into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Runs `future` to completion on a new runtime.
+    #[inline]
+    fn block_on<F>(future: F) -> F::Output
+    where
+        F: core::future::Future,
+    {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("Unable to build runtime.")
+            .block_on(future)
+    }
+
+    /// Checks that the legacy header derives the same keys as earlier signer versions, which used
+    /// [`Argon2::default`] with the legacy salt.
+    #[test]
+    fn legacy_header_matches_default_hasher() {
+        let header = KdfHeader::default();
+        let salt = SaltString::b64_encode(LEGACY_SALT).expect("The legacy salt is valid.");
+        assert_eq!(header.salt_string(), salt);
+        let password = b"legacy password";
+        assert_eq!(
+            header
+                .hasher()
+                .hash_password(password, &header.salt_string())
+                .expect("Unable to hash password.")
+                .to_string(),
+            Argon2::default()
+                .hash_password(password, &salt)
+                .expect("Unable to hash password.")
+                .to_string()
+        );
+    }
+
+    /// Checks that headers below the recommended costs are weak and that new headers get
+    /// distinct valid salts.
+    #[test]
+    fn flags_weak_headers() {
+        assert!(KdfHeader::default().is_weak());
+        assert!(KdfHeader::new(MIN_MEMORY_COST, MIN_TIME_COST - 1).is_weak());
+        assert!(!KdfHeader::new(MIN_MEMORY_COST, MIN_TIME_COST).is_weak());
+        let header = KdfHeader::new(8, 1);
+        assert_ne!(header.salt, KdfHeader::new(8, 1).salt);
+        assert!(header
+            .hasher()
+            .hash_password(b"password", &header.salt_string())
+            .is_ok());
+    }
+
+    /// Checks that headers are saved and loaded back, and that a missing file loads as the
+    /// legacy header.
+    #[test]
+    fn persists_headers() {
+        let directory = tempfile::tempdir().expect("Unable to create temporary directory.");
+        let path = directory.path().join("kdf.json");
+        block_on(async {
+            assert_eq!(
+                KdfHeader::load(&path).await.expect("Unable to load."),
+                KdfHeader::default()
+            );
+            let header = KdfHeader::new(MIN_MEMORY_COST, MIN_TIME_COST);
+            header.save(&path).await.expect("Unable to save.");
+            assert_eq!(
+                KdfHeader::load(&path).await.expect("Unable to load."),
+                header
+            );
+        });
+    }
+
+    /// Checks that headers with an unknown version, invalid costs or an invalid salt are
+    /// rejected when they are loaded.
+    #[test]
+    fn rejects_invalid_headers() {
+        let directory = tempfile::tempdir().expect("Unable to create temporary directory.");
+        let path = directory.path().join("kdf.json");
+        let valid = KdfHeader::new(MIN_MEMORY_COST, MIN_TIME_COST);
+        let invalid = [
+            KdfHeader {
+                version: VERSION + 1,
+                ..valid.clone()
+            },
+            KdfHeader {
+                memory_cost: 0,
+                ..valid.clone()
+            },
+            KdfHeader {
+                time_cost: 0,
+                ..valid.clone()
+            },
+            KdfHeader {
+                salt: "not a salt!".into(),
+                ..valid
+            },
+        ];
+        block_on(async {
+            for header in invalid {
+                header.save(&path).await.expect("Unable to save.");
+                assert_eq!(
+                    KdfHeader::load(&path)
+                        .await
+                        .expect_err("The header is invalid.")
+                        .kind(),
+                    io::ErrorKind::InvalidData,
+                    "{header:?}"
+                );
+            }
+        });
+    }
+
+    /// Checks that pending files sit next to the file they replace.
+    #[test]
+    fn appends_pending_extension() {
+        assert_eq!(
+            pending_path(Path::new("profile/storage.data")),
+            PathBuf::from("profile/storage.data.rekey")
+        );
+    }
+}
@@ -22,13 +22,25 @@
 
 extern crate alloc;
 
+pub mod address_book;
+pub mod asset;
+pub mod attempts;
+pub mod authorization;
+pub mod backup;
 pub mod config;
+pub mod history;
 pub mod http;
+pub mod kdf;
 pub mod log;
+pub mod mnemonic;
+pub mod network;
 pub mod parameters;
+pub mod registry;
 pub mod secret;
 pub mod service;
+pub mod shares;
 pub mod storage;
+pub mod wallet;
 
 #[doc(inline)]
 pub use manta_accounting;
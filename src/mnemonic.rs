@@ -0,0 +1,419 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Recovery Phrase Validation
+//!
+//! Recovery phrases typed in by the user are validated so that a mistyped phrase is reported with
+//! the exact problem instead of being rejected as a whole. Only English phrases are supported,
+//! the other BIP39 wordlists are only used to tell the user which language a phrase is in.
+//!
+//! # Supported Languages
+//!
+//! Only English recovery phrases can be used to create or recover a wallet. The `manta-pay` key
+//! secret is built from an English [`Mnemonic`], and the BIP39 seed is derived from the words of
+//! the phrase, so a phrase in another language cannot be translated into an English one for the
+//! same wallet. Supporting other languages needs `manta-pay` to accept them and is out of scope
+//! here: phrases in the other wordlists are still recognized so that [`parse`] can report
+//! [`MnemonicError::UnsupportedWordlist`] instead of a list of unknown words.
+
+use bip0039::{
+    ChineseSimplified, ChineseTraditional, Czech, English, French, Italian, Japanese, Korean,
+    Language, Portuguese, Spanish,
+};
+use core::fmt::{self, Display};
+use manta_pay::key::Mnemonic;
+use manta_util::serde::{Deserialize, Serialize};
+use std::error;
+use unicode_normalization::UnicodeNormalization;
+
+/// Valid Recovery Phrase Lengths
+pub const WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+
+/// Maximum Number of Suggestions for an Unknown Word
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Maximum Edit Distance of a Suggestion
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// BIP39 Wordlist
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub enum Wordlist {
+    /// English
+    English,
+
+    /// Simplified Chinese
+    ChineseSimplified,
+
+    /// Traditional Chinese
+    ChineseTraditional,
+
+    /// Czech
+    Czech,
+
+    /// French
+    French,
+
+    /// Italian
+    Italian,
+
+    /// Japanese
+    Japanese,
+
+    /// Korean
+    Korean,
+
+    /// Portuguese
+    Portuguese,
+
+    /// Spanish
+    Spanish,
+}
+
+impl Wordlist {
+    /// All Wordlists
+    ///
+    /// English comes first so that it wins when a phrase matches several wordlists equally well.
+    pub const ALL: [Self; 10] = [
+        Self::English,
+        Self::ChineseSimplified,
+        Self::ChineseTraditional,
+        Self::Czech,
+        Self::French,
+        Self::Italian,
+        Self::Japanese,
+        Self::Korean,
+        Self::Portuguese,
+        Self::Spanish,
+    ];
+
+    /// Returns the words of `self`.
+    #[inline]
+    pub fn words(self) -> &'static [&'static str; 2048] {
+        match self {
+            Self::English => English::word_list(),
+            Self::ChineseSimplified => ChineseSimplified::word_list(),
+            Self::ChineseTraditional => ChineseTraditional::word_list(),
+            Self::Czech => Czech::word_list(),
+            Self::French => French::word_list(),
+            Self::Italian => Italian::word_list(),
+            Self::Japanese => Japanese::word_list(),
+            Self::Korean => Korean::word_list(),
+            Self::Portuguese => Portuguese::word_list(),
+            Self::Spanish => Spanish::word_list(),
+        }
+    }
+
+    /// Returns `true` if the normalized `word` is in `self`.
+    #[inline]
+    pub fn contains(self, word: &str) -> bool {
+        self.words()
+            .iter()
+            .any(|candidate| candidate.nfkd().eq(word.chars()))
+    }
+
+    /// Returns up to [`MAX_SUGGESTIONS`] words of `self` which are closest to the normalized
+    /// `word`, closest first.
+    #[inline]
+    pub fn suggestions(self, word: &str) -> Vec<String> {
+        let mut candidates = self
+            .words()
+            .iter()
+            .filter_map(|candidate| {
+                let distance = distance(word, candidate);
+                (distance <= MAX_SUGGESTION_DISTANCE).then_some((distance, *candidate))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort();
+        candidates
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, candidate)| candidate.to_owned())
+            .collect()
+    }
+
+    /// Returns `true` if `phrase` passes the checksum of `self`.
+    #[inline]
+    fn checks(self, phrase: &str) -> bool {
+        match self {
+            Self::English => bip0039::Mnemonic::<English>::from_phrase(phrase).is_ok(),
+            Self::ChineseSimplified => {
+                bip0039::Mnemonic::<ChineseSimplified>::from_phrase(phrase).is_ok()
+            }
+            Self::ChineseTraditional => {
+                bip0039::Mnemonic::<ChineseTraditional>::from_phrase(phrase).is_ok()
+            }
+            Self::Czech => bip0039::Mnemonic::<Czech>::from_phrase(phrase).is_ok(),
+            Self::French => bip0039::Mnemonic::<French>::from_phrase(phrase).is_ok(),
+            Self::Italian => bip0039::Mnemonic::<Italian>::from_phrase(phrase).is_ok(),
+            Self::Japanese => bip0039::Mnemonic::<Japanese>::from_phrase(phrase).is_ok(),
+            Self::Korean => bip0039::Mnemonic::<Korean>::from_phrase(phrase).is_ok(),
+            Self::Portuguese => bip0039::Mnemonic::<Portuguese>::from_phrase(phrase).is_ok(),
+            Self::Spanish => bip0039::Mnemonic::<Spanish>::from_phrase(phrase).is_ok(),
+        }
+    }
+}
+
+impl Display for Wordlist {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Unknown Word
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct UnknownWord {
+    /// Position of the Word, starting from one
+    pub position: usize,
+
+    /// Word
+    pub word: String,
+
+    /// Closest Valid Words
+    pub suggestions: Vec<String>,
+}
+
+/// Recovery Phrase Error
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(
+    content = "content",
+    crate = "manta_util::serde",
+    deny_unknown_fields,
+    tag = "type"
+)]
+pub enum MnemonicError {
+    /// Wrong Length Error
+    ///
+    /// The phrase has the given number of words, which is not one of [`WORD_COUNTS`].
+    WrongLength(usize),
+
+    /// Unknown Words Error
+    ///
+    /// Some words of the phrase are not in the wordlist the rest of the phrase is written in.
+    UnknownWords {
+        /// Wordlist of the Phrase
+        wordlist: Wordlist,
+
+        /// Unknown Words
+        words: Vec<UnknownWord>,
+    },
+
+    /// Bad Checksum Error
+    ///
+    /// Every word is valid but the phrase does not pass its checksum, so words were swapped,
+    /// replaced by other valid words or left out.
+    BadChecksum,
+
+    /// Unsupported Wordlist Error
+    ///
+    /// The phrase is a valid phrase in the given wordlist, but keys are only derived from English
+    /// phrases. The seed of a BIP39 phrase depends on its words, so converting it to English
+    /// would give a different wallet.
+    UnsupportedWordlist(Wordlist),
+}
+
+impl Display for MnemonicError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::WrongLength(count) => write!(
+                f,
+                "recovery phrases have 12, 15, 18, 21 or 24 words but {count} were given"
+            ),
+            Self::UnknownWords { wordlist, words } => {
+                write!(f, "unknown {wordlist} words:")?;
+                for word in words {
+                    write!(f, " {} at position {}", word.word, word.position)?;
+                    if !word.suggestions.is_empty() {
+                        write!(f, " (did you mean {}?)", word.suggestions.join(", "))?;
+                    }
+                }
+                Ok(())
+            }
+            Self::BadChecksum => write!(f, "the recovery phrase does not pass its checksum"),
+            Self::UnsupportedWordlist(wordlist) => write!(
+                f,
+                "{wordlist} recovery phrases are not supported, only English phrases are"
+            ),
+        }
+    }
+}
+
+impl error::Error for MnemonicError {}
+
+/// Returns the Levenshtein distance between the normalized `lhs` and `rhs`, counted in
+/// characters.
+#[inline]
+fn distance(lhs: &str, rhs: &str) -> usize {
+    let rhs = rhs.nfkd().collect::<Vec<_>>();
+    let mut previous = (0..=rhs.len()).collect::<Vec<_>>();
+    let mut current = vec![0; rhs.len() + 1];
+    for (i, l) in lhs.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, r) in rhs.iter().enumerate() {
+            current[j + 1] = (previous[j] + usize::from(l != *r))
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        core::mem::swap(&mut previous, &mut current);
+    }
+    previous[rhs.len()]
+}
+
+/// Splits `phrase` into normalized words.
+#[inline]
+fn words(phrase: &str) -> Vec<String> {
+    phrase
+        .split_whitespace()
+        .map(|word| word.nfkd().collect::<String>().to_lowercase())
+        .collect()
+}
+
+/// Validates `phrase`, returning the [`Wordlist`] it is written in.
+///
+/// Words are matched case-insensitively and after Unicode normalization, and the wordlist is
+/// the one containing the most words of the phrase.
+#[inline]
+pub fn validate(phrase: &str) -> Result<Wordlist, MnemonicError> {
+    let words = words(phrase);
+    if !WORD_COUNTS.contains(&words.len()) {
+        return Err(MnemonicError::WrongLength(words.len()));
+    }
+    let wordlist = Wordlist::ALL
+        .into_iter()
+        .rev()
+        .max_by_key(|wordlist| words.iter().filter(|word| wordlist.contains(word)).count())
+        .unwrap_or(Wordlist::English);
+    let unknown_words = words
+        .iter()
+        .enumerate()
+        .filter(|(_, word)| !wordlist.contains(word))
+        .map(|(position, word)| UnknownWord {
+            position: position + 1,
+            word: word.clone(),
+            suggestions: wordlist.suggestions(word),
+        })
+        .collect::<Vec<_>>();
+    if !unknown_words.is_empty() {
+        return Err(MnemonicError::UnknownWords {
+            wordlist,
+            words: unknown_words,
+        });
+    }
+    let separator = if wordlist == Wordlist::Japanese {
+        "\u{3000}"
+    } else {
+        " "
+    };
+    if !wordlist.checks(&words.join(separator)) {
+        return Err(MnemonicError::BadChecksum);
+    }
+    Ok(wordlist)
+}
+
+/// Parses `phrase` into a [`Mnemonic`], which must be a valid English recovery phrase.
+///
+/// Valid phrases in other wordlists are rejected with [`MnemonicError::UnsupportedWordlist`], see
+/// the [module documentation](self) for why.
+#[inline]
+pub fn parse(phrase: &str) -> Result<Mnemonic, MnemonicError> {
+    match validate(phrase)? {
+        Wordlist::English => {
+            Mnemonic::new(&words(phrase).join(" ")).map_err(|_| MnemonicError::BadChecksum)
+        }
+        wordlist => Err(MnemonicError::UnsupportedWordlist(wordlist)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Valid English Phrase
+    const ENGLISH: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    /// Valid Italian Phrase
+    const ITALIAN: &str = "abaco abaco abaco abaco abaco abaco abaco abaco abaco abaco abaco abete";
+
+    /// Checks that valid English phrases are parsed regardless of case and spacing.
+    #[test]
+    fn parses_english_phrases() {
+        assert_eq!(validate(ENGLISH), Ok(Wordlist::English));
+        assert!(parse(ENGLISH).is_ok());
+        let shouted = format!("  {}  ", ENGLISH.to_uppercase().replace(' ', "   "));
+        assert_eq!(validate(&shouted), Ok(Wordlist::English));
+        assert!(parse(&shouted).is_ok());
+    }
+
+    /// Checks that phrases with a length which is not in [`WORD_COUNTS`] are rejected.
+    #[test]
+    fn rejects_wrong_lengths() {
+        assert_eq!(validate(""), Err(MnemonicError::WrongLength(0)));
+        assert_eq!(
+            validate("abandon abandon about"),
+            Err(MnemonicError::WrongLength(3))
+        );
+    }
+
+    /// Checks that unknown words are reported with their positions and the closest valid words.
+    #[test]
+    fn suggests_unknown_words() {
+        let phrase = ENGLISH.replacen("abandon abandon abandon", "abandon abandon abandn", 1);
+        match validate(&phrase) {
+            Err(MnemonicError::UnknownWords { wordlist, words }) => {
+                assert_eq!(wordlist, Wordlist::English);
+                assert_eq!(words.len(), 1);
+                assert_eq!(words[0].position, 3);
+                assert_eq!(words[0].word, "abandn");
+                assert_eq!(
+                    words[0].suggestions.first().map(String::as_str),
+                    Some("abandon")
+                );
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    /// Checks that phrases of valid words which do not pass their checksum are rejected.
+    #[test]
+    fn rejects_bad_checksums() {
+        let phrase = ENGLISH.replace("about", "abandon");
+        assert_eq!(validate(&phrase), Err(MnemonicError::BadChecksum));
+        assert_eq!(parse(&phrase).err(), Some(MnemonicError::BadChecksum));
+    }
+
+    /// Checks that valid phrases in other languages are recognized but not parsed.
+    #[test]
+    fn rejects_unsupported_wordlists() {
+        assert_eq!(validate(ITALIAN), Ok(Wordlist::Italian));
+        assert_eq!(
+            parse(ITALIAN).err(),
+            Some(MnemonicError::UnsupportedWordlist(Wordlist::Italian))
+        );
+    }
+
+    /// Checks that the edit distance counts characters and not bytes.
+    #[test]
+    fn measures_distance() {
+        assert_eq!(distance("abandon", "abandon"), 0);
+        assert_eq!(distance("abandn", "abandon"), 1);
+        assert_eq!(distance("abaco", "ábaco"), 1);
+        assert_eq!(distance("", "abc"), 3);
+    }
+}
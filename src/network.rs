@@ -0,0 +1,135 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Networks
+//!
+//! Besides the networks built into `manta-pay`, the signer can serve custom networks, like local
+//! development chains or staging forks, which are configured by name. A custom network never
+//! shares its signer state, transaction history or custom assets with any other network.
+
+use crate::config::NETWORKS;
+use core::fmt::{self, Display};
+use manta_pay::signer::client::network::Network;
+use manta_util::serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Network Identifier
+///
+/// Built-in networks have the same encoding as [`Network`], and any other string names a custom
+/// network.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", untagged)]
+pub enum NetworkId {
+    /// Built-in Network
+    Builtin(Network),
+
+    /// Custom Network
+    Custom(String),
+}
+
+impl NetworkId {
+    /// Returns the built-in network if `self` names one.
+    #[inline]
+    pub fn builtin(&self) -> Option<Network> {
+        match self {
+            Self::Builtin(network) => Some(*network),
+            _ => None,
+        }
+    }
+
+    /// Returns the network called `name`, which is the inverse of [`name`](Self::name).
+    #[inline]
+    pub fn from_name(name: &str) -> Self {
+        NETWORKS
+            .into_iter()
+            .map(Self::from)
+            .find(|network| network.name() == name)
+            .unwrap_or_else(|| Self::Custom(name.to_owned()))
+    }
+
+    /// Returns the name of the network, which is also used in its file names.
+    #[inline]
+    pub fn name(&self) -> String {
+        match self {
+            Self::Builtin(network) => network.to_string().to_lowercase(),
+            Self::Custom(name) => name.clone(),
+        }
+    }
+}
+
+impl Display for NetworkId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Builtin(network) => write!(f, "{network}"),
+            Self::Custom(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl From<Network> for NetworkId {
+    #[inline]
+    fn from(network: Network) -> Self {
+        Self::Builtin(network)
+    }
+}
+
+/// Returns `true` if `name` can be used for a custom network. Names are used in file names so they
+/// are restricted to ASCII lowercase letters, digits, `-` and `_`, and they cannot be the name of
+/// a built-in network.
+#[inline]
+pub fn is_valid_custom_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        && !NETWORKS
+            .into_iter()
+            .any(|network| NetworkId::from(network).name() == name)
+}
+
+/// Custom Network
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct CustomNetwork {
+    /// Network Name
+    pub name: String,
+
+    /// Proving Directory
+    ///
+    /// Directory holding the proving contexts used by this network. The network uses the proving
+    /// contexts shipped with the signer if this is not set.
+    pub proving_directory: Option<PathBuf>,
+}
+
+impl CustomNetwork {
+    /// Builds a new [`CustomNetwork`] called `name` which uses the proving contexts shipped with
+    /// the signer.
+    #[inline]
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            proving_directory: None,
+        }
+    }
+
+    /// Returns the [`NetworkId`] of `self`.
+    #[inline]
+    pub fn id(&self) -> NetworkId {
+        NetworkId::Custom(self.name.clone())
+    }
+}
@@ -16,76 +16,254 @@
 
 //! Manta Signer SDK Parameter Loading
 
-// TODO: Report a more informative error.
-
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use core::fmt::{self, Display};
 use manta_parameters::Get;
 use manta_pay::{config, parameters::load_transfer_parameters, signer::base::SignerParameters};
 use manta_util::codec::{Decode, IoReader};
+use sha2::{Digest, Sha256};
 use std::{
-    fs::{self, File},
+    error, fs, io, panic,
     path::{Path, PathBuf},
+    thread,
 };
 
-/// Loads the [`SignerParameters`] from the Manta SDK.
+/// Parameter Loading Error
+#[derive(Debug)]
+pub enum ParameterError {
+    /// Not Found Error
+    ///
+    /// The parameter file does not exist.
+    NotFound(PathBuf),
+
+    /// Unreadable Error
+    ///
+    /// The parameter file exists but could not be read.
+    Unreadable(PathBuf, io::Error),
+
+    /// Decoding Error
+    ///
+    /// The parameter file was read but does not hold valid parameters.
+    Decode(PathBuf),
+
+    /// Checksum Mismatch Error
+    ///
+    /// The parameter file does not match the checksum it was released with.
+    ChecksumMismatch(PathBuf),
+
+    /// Embedded Parameter Error
+    ///
+    /// The parameters compiled into the signer under the given name could not be decoded.
+    Embedded(&'static str),
+}
+
+impl ParameterError {
+    /// Builds a [`ParameterError`] for an I/O error `err` raised while reading `path`.
+    #[inline]
+    fn io(path: PathBuf, err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => Self::NotFound(path),
+            _ => Self::Unreadable(path, err),
+        }
+    }
+}
+
+impl Display for ParameterError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound(path) => write!(f, "{} was not found", path.display()),
+            Self::Unreadable(path, err) => write!(f, "{} could not be read: {err}", path.display()),
+            Self::Decode(path) => write!(f, "{} could not be decoded", path.display()),
+            Self::ChecksumMismatch(path) => {
+                write!(f, "{} does not match its checksum", path.display())
+            }
+            Self::Embedded(name) => write!(f, "embedded {name} could not be decoded"),
+        }
+    }
+}
+
+impl error::Error for ParameterError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Unreadable(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Proving Context Checksums
+///
+/// SHA-256 checksums of the proving contexts shipped with the signer, computed from
+/// `ui/src-tauri/proving` at build time. A proving context which was missing at build time has no
+/// checksum and is always refused.
+const PROVING_CHECKSUMS: [(&str, Option<[u8; 32]>); 3] =
+    include!(concat!(env!("OUT_DIR"), "/proving_checksums.rs"));
+
+/// Proving Context Cache Magic Bytes
+///
+/// Prefix of every proving context cache file, which also versions the cache format.
+const CACHE_MAGIC: &[u8; 8] = b"MSPCTX01";
+
+/// Loads the [`SignerParameters`] using the proving contexts shipped with the signer, which are
+/// stored in `proving_directory`, using the proving context cache in `cache_directory` if it is
+/// set.
+///
+/// Every proving context is checked against the checksum it was released with before it is
+/// decoded, so tampered or truncated proving contexts are refused before any proof is made.
 #[inline]
-pub fn load<P>(directory: P) -> Option<SignerParameters>
+pub fn load<P>(
+    proving_directory: P,
+    cache_directory: Option<&Path>,
+) -> Result<SignerParameters, ParameterError>
 where
     P: AsRef<Path>,
 {
-    let mut directory = directory.as_ref().to_owned();
-    directory.push("sdk");
-    directory.push("data");
-    directory.push("pay");
-    directory.push("testnet");
-    directory.push("proving");
-    fs::create_dir_all(&directory).ok()?;
-    let parameters = load_transfer_parameters();
-    let mut exec_dir =
-        std::env::current_exe().expect("Could not get Manta Signer executable file directory");
-    exec_dir.pop();
-
-    // MacOs installation puts assets in another folder "Resources" compared to Win/Linux Installations
-    let mut directory_check = PathBuf::from(&exec_dir).join("proving");
-
-    // check for test server and MacOs installation folder hierachy discrepancy relative to Win/Ubuntu
-    if !directory_check.is_dir() {
-        exec_dir.pop();
-        directory_check = PathBuf::from(&exec_dir).join("proving");
-
-        if !directory_check.is_dir() {
-            if cfg!(target_os = "macos") {
-                // macos
-                exec_dir.push("Resources");
-            } else {
-                // linux (ubuntu) specific
-                exec_dir = PathBuf::from("/usr/lib/manta-signer");
-            }
-        }
+    load_proving_contexts(proving_directory.as_ref(), true, cache_directory)
+}
+
+/// Loads the [`SignerParameters`] using the proving contexts in `proving_directory` instead of
+/// the ones shipped with the signer, using the proving context cache in `cache_directory` if it
+/// is set. These proving contexts are not checked against any checksum.
+#[inline]
+pub fn load_from_proving_directory<P>(
+    proving_directory: P,
+    cache_directory: Option<&Path>,
+) -> Result<SignerParameters, ParameterError>
+where
+    P: AsRef<Path>,
+{
+    load_proving_contexts(proving_directory.as_ref(), false, cache_directory)
+}
+
+/// Reads the proving context stored at `path`, checking it against `checksum` if `verify` is
+/// set, and decodes it.
+///
+/// If `cache_directory` is set, the proving context is read from the cache entry of its checksum
+/// when there is one, and a cache entry is written for it otherwise.
+#[inline]
+fn decode_proving_context(
+    path: &Path,
+    checksum: Option<[u8; 32]>,
+    verify: bool,
+    cache_directory: Option<&Path>,
+) -> Result<config::ProvingContext, ParameterError> {
+    let data = fs::read(path).map_err(|err| ParameterError::io(path.to_owned(), err))?;
+    let digest = Sha256::digest(&data);
+    if verify && !matches!(checksum, Some(checksum) if digest.as_slice() == checksum) {
+        return Err(ParameterError::ChecksumMismatch(path.to_owned()));
+    }
+    let cache_path = cache_directory.map(|directory| directory.join(cache_file_name(&digest)));
+    if let Some(context) = cache_path.as_deref().and_then(read_cache) {
+        return Ok(context);
+    }
+    let context = config::ProvingContext::decode(IoReader(data.as_slice()))
+        .map_err(|_| ParameterError::Decode(path.to_owned()))?;
+    if let Some(cache_path) = cache_path {
+        // NOTE: The cache only speeds up the next startup, so failing to write it is not an
+        //       error and the next startup tries again.
+        let _ = write_cache(&cache_path, &context);
     }
-    // use absolute paths for release
-    let mut to_private = PathBuf::from(&exec_dir);
-    to_private.push("proving/to-private.lfs");
+    Ok(context)
+}
 
-    let mut private_transfer = PathBuf::from(&exec_dir);
-    private_transfer.push("proving/private-transfer.lfs");
+/// Returns the name of the cache file for the proving context with the given `checksum`.
+#[inline]
+fn cache_file_name(checksum: &[u8]) -> String {
+    checksum
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .chain(Some(".cache".into()))
+        .collect()
+}
 
-    let mut to_public = PathBuf::from(&exec_dir);
-    to_public.push("proving/to-public.lfs");
+/// Reads the proving context cache entry stored at `path`.
+///
+/// Cache entries hold the SHA-256 checksum of their body after the [`CACHE_MAGIC`], so that
+/// truncated or corrupted entries are ignored. The body stores the curve points uncompressed and
+/// is decoded without validating them, since they were validated when the entry was written.
+#[inline]
+fn read_cache(path: &Path) -> Option<config::ProvingContext> {
+    let data = fs::read(path).ok()?;
+    let body = data.strip_prefix(CACHE_MAGIC.as_slice())?;
+    if body.len() < 32 {
+        return None;
+    }
+    let (checksum, body) = body.split_at(32);
+    if Sha256::digest(body).as_slice() != checksum {
+        return None;
+    }
+    CanonicalDeserialize::deserialize_unchecked(body)
+        .ok()
+        .map(config::ProvingContext)
+}
+
+/// Writes the cache entry for `context` to `path`.
+///
+/// The entry is written to a temporary file first so that an interrupted write never leaves a
+/// partial entry at `path`.
+#[inline]
+fn write_cache(path: &Path, context: &config::ProvingContext) -> io::Result<()> {
+    let mut body = Vec::with_capacity(context.0.uncompressed_size());
+    context
+        .0
+        .serialize_unchecked(&mut body)
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    let mut data = Vec::with_capacity(CACHE_MAGIC.len() + 32 + body.len());
+    data.extend_from_slice(CACHE_MAGIC);
+    data.extend_from_slice(&Sha256::digest(&body));
+    data.extend_from_slice(&body);
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    let temporary_path = path.with_extension("tmp");
+    fs::write(&temporary_path, data)?;
+    fs::rename(temporary_path, path)
+}
+
+/// Waits for the scoped `thread` to finish, resuming its panic if it had one.
+#[inline]
+fn join<T>(thread: thread::ScopedJoinHandle<T>) -> T {
+    thread
+        .join()
+        .unwrap_or_else(|err| panic::resume_unwind(err))
+}
 
-    Some(SignerParameters {
+/// Loads the [`SignerParameters`] using the proving contexts in `proving_directory`, checking
+/// them against [`PROVING_CHECKSUMS`] if `verify` is set and using the proving context cache in
+/// `cache_directory` if it is set.
+///
+/// The three proving contexts are decoded on separate threads since decoding dominates the
+/// startup time of the signer.
+#[inline]
+fn load_proving_contexts(
+    proving_directory: &Path,
+    verify: bool,
+    cache_directory: Option<&Path>,
+) -> Result<SignerParameters, ParameterError> {
+    let [to_private, private_transfer, to_public] =
+        PROVING_CHECKSUMS.map(|(name, checksum)| (proving_directory.join(name), checksum));
+    let decode = |(path, checksum): &(PathBuf, Option<[u8; 32]>)| {
+        decode_proving_context(path, *checksum, verify, cache_directory)
+    };
+    let (to_private, private_transfer, to_public, parameters) = thread::scope(|scope| {
+        let to_private = scope.spawn(|| decode(&to_private));
+        let private_transfer = scope.spawn(|| decode(&private_transfer));
+        let to_public = scope.spawn(|| decode(&to_public));
+        let parameters = load_transfer_parameters();
+        (
+            join(to_private),
+            join(private_transfer),
+            join(to_public),
+            parameters,
+        )
+    });
+    Ok(SignerParameters {
         proving_context: config::MultiProvingContext {
-            to_private: config::ProvingContext::decode(IoReader(
-                File::open(to_private).expect("Could not read to_private.lfs"),
-            ))
-            .ok()?,
-            private_transfer: config::ProvingContext::decode(IoReader(
-                File::open(private_transfer).expect("Could not read private_transfer.lfs"),
-            ))
-            .ok()?,
-            to_public: config::ProvingContext::decode(IoReader(
-                File::open(to_public).expect("Could not read to_public.lfs"),
-            ))
-            .ok()?,
+            to_private: to_private?,
+            private_transfer: private_transfer?,
+            to_public: to_public?,
         },
         parameters,
     })
@@ -93,9 +271,8 @@ where
 
 /// Loads the \[`UtxoAccumulatorModel`\](config::UtxoAccumulatorModel) from the Manta SDK.
 #[inline]
-pub fn load_utxo_accumulator_model() -> Option<config::UtxoAccumulatorModel> {
-    config::UtxoAccumulatorModel::decode(
-        manta_parameters::pay::parameters::UtxoAccumulatorModel::get()?,
-    )
-    .ok()
+pub fn load_utxo_accumulator_model() -> Result<config::UtxoAccumulatorModel, ParameterError> {
+    manta_parameters::pay::parameters::UtxoAccumulatorModel::get()
+        .and_then(|data| config::UtxoAccumulatorModel::decode(data).ok())
+        .ok_or(ParameterError::Embedded("UtxoAccumulatorModel"))
 }
@@ -0,0 +1,345 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Asset Registry
+//!
+//! The asset metadata in a sign request comes from the dApp and cannot be trusted on its own. The
+//! signer keeps a registry of known assets per network, made of the assets bundled with the signer
+//! and the custom assets added by the user, and checks the incoming metadata against it before the
+//! transaction is shown to the user.
+//!
+//! The bundled assets are listed in `registry.json` next to this module. They are the native
+//! assets of the built-in networks, whose symbol and decimals are both checked, so a dApp cannot
+//! pass another asset off as DOL, KMA or MANTA, or misstate their decimals.
+
+use crate::{asset, network::NetworkId};
+use alloc::collections::BTreeMap;
+use manta_pay::{
+    config::AssetId,
+    signer::{AssetMetadata, TokenType},
+};
+use manta_util::serde::{Deserialize, Serialize};
+use once_cell::sync::Lazy;
+use std::{io, path::Path};
+use tokio::fs;
+
+/// Bundled Registry
+///
+/// Assets bundled with the signer, keyed by network name.
+const BUNDLED_REGISTRY: &str = include_str!("registry.json");
+
+/// Decoded Bundled Registry
+static BUNDLED: Lazy<BTreeMap<String, Vec<RegisteredAsset>>> = Lazy::new(|| {
+    serde_json::from_str(BUNDLED_REGISTRY).expect("The bundled asset registry is valid.")
+});
+
+/// Registered Token Type
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub enum RegisteredType {
+    /// Fungible Asset with the given number of Decimals
+    Fungible(u32),
+
+    /// Non-Fungible Asset
+    NonFungible,
+
+    /// Unknown Token Type
+    ///
+    /// Only the symbol of the asset is checked. Bundled assets always have a known token type.
+    Unknown,
+}
+
+/// Registered Asset
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct RegisteredAsset {
+    /// Asset Id
+    pub id: u128,
+
+    /// Symbol
+    pub symbol: String,
+
+    /// Token Type
+    pub token_type: RegisteredType,
+}
+
+impl RegisteredAsset {
+    /// Returns `true` if `metadata` describes the same asset as `self`.
+    #[inline]
+    pub fn matches(&self, metadata: &AssetMetadata) -> bool {
+        let token_type = match metadata.token_type {
+            TokenType::FT(decimals) => RegisteredType::Fungible(decimals),
+            TokenType::NFT => RegisteredType::NonFungible,
+        };
+        self.symbol == metadata.symbol
+            && (self.token_type == RegisteredType::Unknown || self.token_type == token_type)
+    }
+
+    /// Returns `true` if `symbol` could be mistaken for the symbol of `self`.
+    #[inline]
+    pub fn is_claimed_by(&self, symbol: &str) -> bool {
+        self.symbol.trim().eq_ignore_ascii_case(symbol.trim())
+    }
+}
+
+/// Returns the assets bundled with the signer for `network`. Custom networks have no bundled
+/// assets.
+#[inline]
+pub fn bundled(network: &NetworkId) -> &'static [RegisteredAsset] {
+    match network {
+        NetworkId::Builtin(network) => BUNDLED
+            .get(&network.to_string())
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+        _ => &[],
+    }
+}
+
+/// Registry Check Result
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Check {
+    /// The metadata matches the registered asset.
+    Match,
+
+    /// The asset is not in the registry, so the metadata cannot be checked.
+    Unregistered,
+
+    /// The metadata does not match the registered asset.
+    Mismatch(RegisteredAsset),
+
+    /// The asset is not in the registry, but its metadata claims the symbol of the registered
+    /// asset, which is how a dApp would disguise one asset as another.
+    Collision(RegisteredAsset),
+}
+
+/// Asset Registry
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct AssetRegistry {
+    /// Custom Assets Added by the User, keyed by Network Name and Asset Id
+    custom: BTreeMap<String, BTreeMap<u128, RegisteredAsset>>,
+}
+
+impl AssetRegistry {
+    /// Loads the custom assets of the registry from `path`, returning an empty registry if the
+    /// file does not exist.
+    #[inline]
+    pub async fn load<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        match fs::read(path).await {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Saves the custom assets of the registry to `path`.
+    #[inline]
+    pub async fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, serde_json::to_vec_pretty(self)?).await
+    }
+
+    /// Returns the registered asset with the given `id` on `network`. Bundled assets take
+    /// precedence over custom assets.
+    #[inline]
+    pub fn get(&self, network: &NetworkId, id: u128) -> Option<RegisteredAsset> {
+        bundled(network)
+            .iter()
+            .find(|asset| asset.id == id)
+            .cloned()
+            .or_else(|| {
+                self.custom
+                    .get(&network.to_string())
+                    .and_then(|assets| assets.get(&id))
+                    .cloned()
+            })
+    }
+
+    /// Returns all the registered assets on `network`, bundled assets first.
+    #[inline]
+    pub fn assets(&self, network: &NetworkId) -> Vec<RegisteredAsset> {
+        let mut assets = bundled(network).to_vec();
+        if let Some(custom) = self.custom.get(&network.to_string()) {
+            let custom = custom
+                .values()
+                .filter(|asset| !assets.iter().any(|bundled| bundled.id == asset.id))
+                .cloned()
+                .collect::<Vec<_>>();
+            assets.extend(custom);
+        }
+        assets
+    }
+
+    /// Adds the custom `asset` on `network`, returning `false` if it has the id or the symbol of
+    /// a bundled asset.
+    #[inline]
+    pub fn insert(&mut self, network: &NetworkId, asset: RegisteredAsset) -> bool {
+        if bundled(network)
+            .iter()
+            .any(|bundled| bundled.id == asset.id || bundled.is_claimed_by(&asset.symbol))
+        {
+            return false;
+        }
+        self.custom
+            .entry(network.to_string())
+            .or_default()
+            .insert(asset.id, asset);
+        true
+    }
+
+    /// Removes the custom asset with the given `id` on `network`, returning it if it was present.
+    #[inline]
+    pub fn remove(&mut self, network: &NetworkId, id: u128) -> Option<RegisteredAsset> {
+        self.custom
+            .get_mut(&network.to_string())
+            .and_then(|assets| assets.remove(&id))
+    }
+
+    /// Checks the dApp-supplied `metadata` for the asset `id` on `network` against the registry.
+    #[inline]
+    pub fn check(&self, network: &NetworkId, id: &AssetId, metadata: &AssetMetadata) -> Check {
+        self.check_numeric(network, asset::id_to_u128(id), metadata)
+    }
+
+    /// Checks `metadata` for the numeric asset `id` on `network`, which is `None` if the asset id
+    /// does not fit into a `u128`.
+    #[inline]
+    fn check_numeric(
+        &self,
+        network: &NetworkId,
+        id: Option<u128>,
+        metadata: &AssetMetadata,
+    ) -> Check {
+        match id.and_then(|id| self.get(network, id)) {
+            Some(registered) if registered.matches(metadata) => Check::Match,
+            Some(registered) => Check::Mismatch(registered),
+            _ => match self
+                .assets(network)
+                .into_iter()
+                .find(|registered| registered.is_claimed_by(&metadata.symbol))
+            {
+                Some(registered) => Check::Collision(registered),
+                _ => Check::Unregistered,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use manta_pay::signer::client::network::Network;
+
+    /// Builds fungible asset metadata with the given `symbol` and `decimals`.
+    #[inline]
+    fn metadata(symbol: &str, decimals: u32) -> AssetMetadata {
+        AssetMetadata {
+            token_type: TokenType::FT(decimals),
+            symbol: symbol.into(),
+        }
+    }
+
+    /// Checks that the bundled registry decodes, registers the native asset of every built-in
+    /// network with its decimals and has no assets for custom networks.
+    #[test]
+    fn decodes_bundled_registry() {
+        for (network, symbol, decimals) in [
+            (Network::Dolphin, "DOL", 18),
+            (Network::Calamari, "KMA", 12),
+            (Network::Manta, "MANTA", 18),
+        ] {
+            assert_eq!(
+                bundled(&network.into()),
+                [RegisteredAsset {
+                    id: 1,
+                    symbol: symbol.into(),
+                    token_type: RegisteredType::Fungible(decimals),
+                }],
+                "{symbol}"
+            );
+        }
+        assert!(bundled(&NetworkId::Custom("local".into())).is_empty());
+    }
+
+    /// Checks that unknown token types only check the symbol, and known ones check the decimals.
+    #[test]
+    fn matches_metadata() {
+        let unknown = RegisteredAsset {
+            id: 1,
+            symbol: "DOL".into(),
+            token_type: RegisteredType::Unknown,
+        };
+        assert!(unknown.matches(&metadata("DOL", 12)));
+        assert!(!unknown.matches(&metadata("KMA", 12)));
+        let fungible = RegisteredAsset {
+            token_type: RegisteredType::Fungible(12),
+            ..unknown
+        };
+        assert!(fungible.matches(&metadata("DOL", 12)));
+        assert!(!fungible.matches(&metadata("DOL", 18)));
+    }
+
+    /// Checks that unregistered assets claiming the symbol of a registered asset are flagged.
+    #[test]
+    fn flags_symbol_collisions() {
+        let network = NetworkId::from(Network::Dolphin);
+        let registry = AssetRegistry::default();
+        assert_eq!(
+            registry.check_numeric(&network, Some(1), &metadata("DOL", 18)),
+            Check::Match
+        );
+        for (symbol, decimals) in [("FAKE", 18), ("DOL", 12), ("DOL", 0)] {
+            assert!(matches!(
+                registry.check_numeric(&network, Some(1), &metadata(symbol, decimals)),
+                Check::Mismatch(registered) if registered.id == 1
+            ));
+        }
+        for symbol in ["DOL", "dol", " DOL "] {
+            assert!(matches!(
+                registry.check_numeric(&network, Some(42), &metadata(symbol, 18)),
+                Check::Collision(registered) if registered.id == 1
+            ));
+        }
+        assert_eq!(
+            registry.check_numeric(&network, Some(42), &metadata("NEW", 18)),
+            Check::Unregistered
+        );
+    }
+
+    /// Checks that custom assets cannot take the id or the symbol of a bundled asset.
+    #[test]
+    fn rejects_conflicting_custom_assets() {
+        let network = NetworkId::from(Network::Dolphin);
+        let mut registry = AssetRegistry::default();
+        let custom = |id, symbol: &str| RegisteredAsset {
+            id,
+            symbol: symbol.into(),
+            token_type: RegisteredType::Fungible(10),
+        };
+        assert!(!registry.insert(&network, custom(1, "NEW")));
+        assert!(!registry.insert(&network, custom(42, "dol")));
+        assert!(registry.insert(&network, custom(42, "NEW")));
+        assert_eq!(registry.get(&network, 42), Some(custom(42, "NEW")));
+        assert_eq!(registry.assets(&network).len(), 2);
+        assert_eq!(registry.remove(&network, 42), Some(custom(42, "NEW")));
+        assert_eq!(registry.get(&network, 42), None);
+    }
+}
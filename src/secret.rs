@@ -18,18 +18,53 @@
 
 // TODO: Use password hashing abstractions from `manta-rs`.
 
-use crate::config::Setup;
+use crate::{attempts::PasswordDelay, config::Setup, kdf::KdfHeader, network::NetworkId};
 use futures::future::BoxFuture;
 use manta_crypto::rand::OsRng;
 use manta_pay::key::Mnemonic;
-use manta_util::serde::Serialize;
+use manta_util::serde::{Deserialize, Deserializer, Serialize, Serializer};
 use password_hash::{PasswordHashString, SaltString};
+use std::path::PathBuf;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 
 pub use password_hash::{Error as PasswordHashError, PasswordHasher, PasswordVerifier};
-pub use secrecy::{ExposeSecret, Secret, SecretString};
+pub use secrecy::{zeroize::Zeroizing, ExposeSecret, Secret, SecretString};
 pub use subtle::{Choice, ConstantTimeEq, CtOption};
 
+/// Returns an empty [`SecretString`], for secrets which are never deserialized.
+#[inline]
+pub fn empty_secret() -> SecretString {
+    SecretString::new(String::new())
+}
+
+/// Serializes the optional `secret`, exposing it to `serializer`.
+///
+/// This is only used for data which is encrypted before it is written, like the wallet metadata.
+#[inline]
+pub fn serialize_optional_secret<S>(
+    secret: &Option<SecretString>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    secret
+        .as_ref()
+        .map(|secret| secret.expose_secret().as_str())
+        .serialize(serializer)
+}
+
+/// Deserializes an optional secret from `deserializer`.
+#[inline]
+pub fn deserialize_optional_secret<'de, D>(
+    deserializer: D,
+) -> Result<Option<SecretString>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.map(SecretString::new))
+}
+
 /// Password Secret Wrapper
 pub struct Password(CtOption<SecretString>);
 
@@ -95,19 +130,113 @@ pub type SetupFuture<'t> = BoxFuture<'t, Setup>;
 /// See its documentation for more.
 pub type PasswordFuture<'t> = BoxFuture<'t, Password>;
 
+/// Profile Future
+///
+/// This `type` is used by the [`select_profile`](Authorizer::select_profile) method of
+/// [`Authorizer`]. See its documentation for more.
+pub type ProfileFuture<'t> = BoxFuture<'t, Option<String>>;
+
+/// Divergence Future
+///
+/// This `type` is used by the [`state_divergence`](Authorizer::state_divergence) method of
+/// [`Authorizer`].
+pub type DivergenceFuture<'t> = BoxFuture<'t, DivergenceChoice>;
+
+/// Recovery Future
+///
+/// This `type` is used by the [`setup_failure`](Authorizer::setup_failure) method of
+/// [`Authorizer`].
+pub type RecoveryFuture<'t> = BoxFuture<'t, RecoveryChoice>;
+
 /// User Selection for different choices the user can make while in the setup phase
 /// of the singer
 pub enum UserSelection {
-    /// Create a new account, generates new recovery phrase
-    Create,
+    /// Create a new account, generates new recovery phrase, protected with an optional BIP39
+    /// passphrase
+    Create(Option<SecretString>),
 
-    /// Recover an old account by providing a recovery phrase
+    /// Recover an old account by providing a recovery phrase and optional BIP39 passphrase
     Recover,
 
+    /// Restore an account from the backup file at `path`, encrypted under `password`
+    ImportBackup {
+        /// Backup File Path
+        path: PathBuf,
+
+        /// Backup Password
+        password: SecretString,
+    },
+
     /// Sign in to an existing account by providing a password
     SignIn,
 }
 
+/// State Divergence
+///
+/// Describes a signer state which was not built from the mnemonic of the wallet, for example
+/// because it was copied in from another wallet.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct StateDivergence {
+    /// Network of the Diverging State
+    pub network: NetworkId,
+
+    /// Fingerprint of the Wallet Mnemonic
+    pub expected: String,
+
+    /// Fingerprint of the Mnemonic of the Diverging State
+    pub found: String,
+}
+
+/// Choices the user can make when a signer state diverges from the wallet
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub enum DivergenceChoice {
+    /// Moves the diverging state aside and rebuilds it from the wallet mnemonic
+    Repair,
+
+    /// Keeps using the diverging state for this session
+    Keep,
+}
+
+/// Setup Failure
+///
+/// Describes an error which stopped the signer from setting up its states.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct SetupFailure {
+    /// Network whose Signer State Failed, if the Failure is Specific to one Network
+    pub network: Option<NetworkId>,
+
+    /// Error Message
+    pub error: String,
+
+    /// Backup Existence Flag
+    ///
+    /// Whether there is a backup of the signer state of `network` which can be restored.
+    pub backup_exists: bool,
+}
+
+/// Choices the user can make to recover from a [`SetupFailure`]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub enum RecoveryChoice {
+    /// Tries the setup again
+    Retry,
+
+    /// Restores the backup of the failed signer state and tries again
+    RestoreBackup,
+
+    /// Creates or recovers an account from a recovery phrase, replacing every signer state
+    RecoverFromMnemonic,
+
+    /// Deletes the failed signer state and tries again
+    DeleteNetwork,
+
+    /// Stops building the signer, returning the error
+    Abort,
+}
+
 /// Authorizer
 pub trait Authorizer: 'static + Send {
     /// Retrieves the password from the authorizer.
@@ -124,6 +253,41 @@ pub trait Authorizer: 'static + Send {
     /// [`Server::start`]: crate::service::Server::start
     fn setup(&mut self, data_exists: bool) -> SetupFuture;
 
+    /// Lets the authorizer pick one of the existing `profiles`, or name a new one, before
+    /// [`setup`](Self::setup) is called. The `current` profile is the one which was selected last.
+    ///
+    /// # Implementation Note
+    ///
+    /// Returning `None` keeps the `current` profile, which is what the default implementation
+    /// does.
+    #[inline]
+    fn select_profile(&mut self, profiles: Vec<String>, current: String) -> ProfileFuture {
+        let _ = (profiles, current);
+        Box::pin(async move { None })
+    }
+
+    /// Reports a `failure` while setting up the signer states, letting the authorizer choose how
+    /// to recover from it.
+    ///
+    /// # Implementation Note
+    ///
+    /// Choices which do not apply to the `failure`, like restoring a backup which does not exist,
+    /// retry the setup instead. By default, [`setup_failure`](Self::setup_failure) aborts.
+    #[inline]
+    fn setup_failure(&mut self, failure: &SetupFailure) -> RecoveryFuture {
+        let _ = failure;
+        Box::pin(async move { RecoveryChoice::Abort })
+    }
+
+    /// Reports the `divergence` of a signer state from the wallet, letting the authorizer choose
+    /// whether it is repaired or kept.
+    ///
+    /// # Implementation Note
+    ///
+    /// There is no default implementation since the signer never continues with a diverging state
+    /// without asking first.
+    fn state_divergence(&mut self, divergence: &StateDivergence) -> DivergenceFuture;
+
     /// Prompts the authorizer with `prompt` so that they can be notified that their password is
     /// requested.
     ///
@@ -144,6 +308,22 @@ pub trait Authorizer: 'static + Send {
         Box::pin(async move {})
     }
 
+    /// Tells the authorizer that the password it sent was wrong and that the next password is only
+    /// checked after `delay`, so that it can show the user how long they have to wait. During a
+    /// lockout, the password it sent was rejected without being checked.
+    ///
+    /// # Implementation Note
+    ///
+    /// The delay grows with the number of failed attempts, see [`PasswordAttempts`] for more. By
+    /// default, [`password_delay`](Self::password_delay) does nothing.
+    ///
+    /// [`PasswordAttempts`]: crate::attempts::PasswordAttempts
+    #[inline]
+    fn password_delay(&mut self, delay: &PasswordDelay) -> UnitFuture {
+        let _ = delay;
+        Box::pin(async move {})
+    }
+
     /// Sends a message to the authorizer to end communication.
     ///
     /// # Implementation Note
@@ -209,6 +389,8 @@ where
     }
 
     /// Returns the hash output as a byte vector.
+    ///
+    /// The output is the key which the encrypted signer files are saved under.
     #[inline]
     pub fn as_bytes(&self) -> Vec<u8> {
         self.hash
@@ -219,6 +401,22 @@ where
     }
 }
 
+impl PasswordHash<Argon2> {
+    /// Builds a new [`PasswordHash`] from `password` using the Argon2 parameters and the salt in
+    /// `header`.
+    #[inline]
+    pub fn from_header(header: &KdfHeader, password: &[u8]) -> Self {
+        let hasher = header.hasher();
+        Self {
+            hash: hasher
+                .hash_password(password, &header.salt_string())
+                .expect("Unable to hash password.")
+                .serialize(),
+            hasher,
+        }
+    }
+}
+
 /// Password Sender
 pub struct PasswordSender {
     /// Password Sender
@@ -228,47 +426,63 @@ pub struct PasswordSender {
     pub retry: Receiver<bool>,
 }
 
+/// Recovery Phrase
+///
+/// Mnemonic together with the optional BIP39 passphrase it was used with.
+pub type RecoveryPhrase = (Mnemonic, Option<SecretString>);
+
 /// Mnemonic Sender
 pub struct MnemonicSender {
     /// Mnemonic Sender
-    pub mnemonic: Sender<Mnemonic>,
+    pub mnemonic: Sender<RecoveryPhrase>,
 
     /// Selection Sender -> Create account or Recover
     pub selection: Sender<UserSelection>,
 
+    /// Divergence Choice Sender
+    pub divergence: Sender<DivergenceChoice>,
+
+    /// Recovery Choice Sender
+    pub recovery: Sender<RecoveryChoice>,
+
     /// Retry Receiver
     pub retry: Receiver<bool>,
 }
 
 impl MnemonicSender {
-    /// Builds a new [`MnemonicSender`] from `mnemonic` and `retry`.
+    /// Builds a new [`MnemonicSender`] from `mnemonic`, `selection`, `divergence`, `recovery` and
+    /// `retry`.
     #[inline]
     pub fn new(
-        mnemonic: Sender<Mnemonic>,
+        mnemonic: Sender<RecoveryPhrase>,
         selection: Sender<UserSelection>,
+        divergence: Sender<DivergenceChoice>,
+        recovery: Sender<RecoveryChoice>,
         retry: Receiver<bool>,
     ) -> Self {
         Self {
             mnemonic,
             selection,
+            divergence,
+            recovery,
             retry,
         }
     }
 
-    /// Loads the mnemonic with `mnemonic` waiting for a retry message.
+    /// Loads the mnemonic with `mnemonic` and `passphrase` waiting for a retry message.
     #[inline]
-    pub async fn load(&mut self, mnemonic: Mnemonic) -> bool {
-        self.load_exact(mnemonic).await;
+    pub async fn load(&mut self, mnemonic: Mnemonic, passphrase: Option<SecretString>) -> bool {
+        self.load_exact(mnemonic, passphrase).await;
         self.retry
             .recv()
             .await
             .expect("Failed to receive retry message.")
     }
 
-    /// Loads the mnemonic with `mnemonic` without requesting a retry message.
+    /// Loads the mnemonic with `mnemonic` and `passphrase` without requesting a retry message.
     #[inline]
-    pub async fn load_exact(&mut self, mnemonic: Mnemonic) {
-        let _ = self.mnemonic.send(mnemonic).await;
+    pub async fn load_exact(&mut self, mnemonic: Mnemonic, passphrase: Option<SecretString>) {
+        let _ = self.mnemonic.send((mnemonic, passphrase)).await;
     }
 
     /// Loads the user selection into the selection channel.
@@ -277,11 +491,23 @@ impl MnemonicSender {
         let _ = self.selection.send(selection).await;
     }
 
+    /// Loads the divergence `choice` into the divergence channel.
+    #[inline]
+    pub async fn load_divergence_choice(&mut self, choice: DivergenceChoice) {
+        let _ = self.divergence.send(choice).await;
+    }
+
+    /// Loads the recovery `choice` into the recovery channel.
+    #[inline]
+    pub async fn load_recovery_choice(&mut self, choice: RecoveryChoice) {
+        let _ = self.recovery.send(choice).await;
+    }
+
     /// Clears the currently stored mnemonic.
     #[inline]
     pub async fn clear(&self) {
         let random_mnemonic = Mnemonic::sample(&mut OsRng);
-        let _ = self.mnemonic.send(random_mnemonic).await;
+        let _ = self.mnemonic.send((random_mnemonic, None)).await;
     }
 }
 
@@ -327,11 +553,17 @@ pub struct PasswordReceiver {
 /// Mnemonic Receiver
 pub struct MnemonicReceiver {
     /// Mnemonic Receiver
-    pub mnemonic: Receiver<Mnemonic>,
+    pub mnemonic: Receiver<RecoveryPhrase>,
 
     /// Selection Receiver
     pub selection: Receiver<UserSelection>,
 
+    /// Divergence Choice Receiver
+    pub divergence: Receiver<DivergenceChoice>,
+
+    /// Recovery Choice Receiver
+    pub recovery: Receiver<RecoveryChoice>,
+
     /// Retry Sender
     pub retry: Sender<bool>,
 }
@@ -363,16 +595,21 @@ impl PasswordReceiver {
 }
 
 impl MnemonicReceiver {
-    /// Builds a new [`MnemonicReceiver`] from `mnemonic` and `retry`.
+    /// Builds a new [`MnemonicReceiver`] from `mnemonic`, `selection`, `divergence`, `recovery`
+    /// and `retry`.
     #[inline]
     pub fn new(
-        mnemonic: Receiver<Mnemonic>,
+        mnemonic: Receiver<RecoveryPhrase>,
         selection: Receiver<UserSelection>,
+        divergence: Receiver<DivergenceChoice>,
+        recovery: Receiver<RecoveryChoice>,
         retry: Sender<bool>,
     ) -> Self {
         Self {
             mnemonic,
             selection,
+            divergence,
+            recovery,
             retry,
         }
     }
@@ -386,9 +623,9 @@ impl MnemonicReceiver {
             .expect("Failed to send retry message.");
     }
 
-    /// Loads the mnemonic from the mnemonic channel.
+    /// Loads the mnemonic and passphrase from the mnemonic channel.
     #[inline]
-    pub async fn load_mnemonic(&mut self) -> Mnemonic {
+    pub async fn load_mnemonic(&mut self) -> RecoveryPhrase {
         self.mnemonic
             .recv()
             .await
@@ -403,6 +640,24 @@ impl MnemonicReceiver {
             .await
             .expect("Failed to load user selection.")
     }
+
+    /// Loads the divergence choice from the divergence channel.
+    #[inline]
+    pub async fn load_divergence_choice(&mut self) -> DivergenceChoice {
+        self.divergence
+            .recv()
+            .await
+            .expect("Failed to load divergence choice.")
+    }
+
+    /// Loads the recovery choice from the recovery channel.
+    #[inline]
+    pub async fn load_recovery_choice(&mut self) -> RecoveryChoice {
+        self.recovery
+            .recv()
+            .await
+            .expect("Failed to load recovery choice.")
+    }
 }
 
 /// Generates a new password-sending channel.
@@ -422,9 +677,23 @@ pub fn mnemonic_channel() -> (MnemonicSender, MnemonicReceiver) {
     let (mnemonic_sender, mnemonic_receiver) = channel(1);
     let (retry_sender, retry_receiver) = channel(1);
     let (selection_sender, selection_receiver) = channel(1);
+    let (divergence_sender, divergence_receiver) = channel(1);
+    let (recovery_sender, recovery_receiver) = channel(1);
     (
-        MnemonicSender::new(mnemonic_sender, selection_sender, retry_receiver),
-        MnemonicReceiver::new(mnemonic_receiver, selection_receiver, retry_sender),
+        MnemonicSender::new(
+            mnemonic_sender,
+            selection_sender,
+            divergence_sender,
+            recovery_sender,
+            retry_receiver,
+        ),
+        MnemonicReceiver::new(
+            mnemonic_receiver,
+            selection_receiver,
+            divergence_receiver,
+            recovery_receiver,
+            retry_sender,
+        ),
     )
 }
 
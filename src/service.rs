@@ -17,16 +17,32 @@
 //! Manta Signer Service Implementation
 
 use crate::{
-    config::{Config, DataExistenceResponse, Setup},
+    address_book::{self, AddressBook, AddressBookEntry},
+    asset,
+    attempts::PasswordAttempts,
+    authorization::{ActionRequest, AuthorizationRequest, AuthorizationWarning},
+    backup::{Backup, BackupError},
+    config::{AssetPolicy, Config, Setup},
+    history::{Entry, ExportFormat, History, HistoryPage, HistoryQuery, Outcome},
     http,
+    kdf::{self, KdfHeader},
     log::{info, trace, warn},
-    secret::{Argon2, Authorizer, ExposeSecret, PasswordHash, SecretString},
+    network::{self, NetworkId},
+    parameters::ParameterError,
+    registry::{AssetRegistry, Check, RegisteredAsset},
+    secret::{
+        Argon2, Authorizer, DivergenceChoice, ExposeSecret, PasswordHash, RecoveryChoice,
+        SecretString, SetupFailure, StateDivergence,
+    },
+    shares::{self, ShareError},
+    wallet::{self, AccountInfo, WalletMetadata, DEFAULT_ACCOUNT},
 };
 use alloc::sync::Arc;
 use core::{
     fmt::{self, Display},
     time::Duration,
 };
+use futures::future::{try_join3, try_join_all};
 use http_types::headers::HeaderValue;
 use manta_accounting::{
     asset::Asset,
@@ -36,24 +52,29 @@ use manta_accounting::{
     wallet::signer::functions::default_authorization_context,
 };
 use manta_pay::{
-    config::{Config as MantaPayConfig, Transaction},
+    config::{AssetId, AssetValue, Config as MantaPayConfig, Transaction},
     key::{Mnemonic, TestnetKeySecret},
     signer::{
         base::{Signer, SignerParameters, SignerState, UtxoAccumulator},
-        client::network::{Message, Network, NetworkSpecific},
-        AssetMetadata, TokenType,
+        AssetMetadata,
     },
 };
 
 /// previous version of manta-pay state. Should use versioning system for SignerState
 use previous_state_manta_pay::signer::base::SignerState as OldSignerState;
 
-use manta_util::{from_variant, serde::Serialize};
+use manta_util::{
+    from_variant,
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+};
 use parking_lot::Mutex;
 use std::{
+    collections::HashMap,
+    ffi::OsString,
     io,
     net::{AddrParseError, SocketAddr},
-    path::Path,
+    path::{Path, PathBuf},
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 use tide::{
     security::{CorsMiddleware, Origin},
@@ -62,22 +83,106 @@ use tide::{
 use tokio::{
     fs,
     sync::Mutex as AsyncMutex,
-    task::{self, JoinError},
+    task::{self, JoinError, JoinHandle},
 };
 
+pub use crate::authorization::display_transaction;
+
 pub use manta_pay::{
     config::{address_to_base58, Address},
     signer::{self, SignError, SignResponse, SyncError, SyncResponse},
 };
 
+/// Account Message
+///
+/// Network message which can target a specific account of the wallet. This has the same encoding
+/// as [`NetworkMessage`] with an additional optional `account` field, so requests which do not
+/// name an account remain valid.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct AccountMessage<T> {
+    /// Network
+    pub network: NetworkId,
+
+    /// Account Index
+    #[serde(default)]
+    pub account: Option<u32>,
+
+    /// Message
+    pub message: T,
+}
+
+impl<T> AccountMessage<T> {
+    /// Builds a new [`AccountMessage`] for `message` on `network` which does not name an account.
+    #[inline]
+    pub fn new<N>(network: N, message: T) -> Self
+    where
+        N: Into<NetworkId>,
+    {
+        Self {
+            network: network.into(),
+            account: None,
+            message,
+        }
+    }
+}
+
+/// Network Message
+///
+/// Network message which can name a custom network. This has the same encoding as [`Message`]
+/// for the built-in networks.
+///
+/// [`Message`]: manta_pay::signer::client::network::Message
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct NetworkMessage<T> {
+    /// Network
+    pub network: NetworkId,
+
+    /// Message
+    pub message: T,
+}
+
+impl<T> NetworkMessage<T> {
+    /// Builds a new [`NetworkMessage`] for `message` on `network`.
+    #[inline]
+    pub fn new<N>(network: N, message: T) -> Self
+    where
+        N: Into<NetworkId>,
+    {
+        Self {
+            network: network.into(),
+            message,
+        }
+    }
+}
+
 /// Synchronization Request
-pub type SyncRequest = Message<signer::SyncRequest>;
+pub type SyncRequest = AccountMessage<signer::SyncRequest>;
 
 /// Signing Request
-pub type SignRequest = Message<signer::SignRequest>;
+pub type SignRequest = AccountMessage<signer::SignRequest>;
 
 /// Receiving Key Request
-pub type ReceivingKeyRequest = Message<signer::GetRequest>;
+pub type ReceivingKeyRequest = AccountMessage<signer::GetRequest>;
+
+/// Balance Request
+pub type BalanceRequest = NetworkMessage<signer::GetRequest>;
+
+/// Balance Response
+///
+/// Private balance of every asset held by the signer on a given network, with one entry per asset
+/// id.
+pub type BalanceResponse = Vec<Asset<AssetId, AssetValue>>;
+
+/// Reset Synchronization State Request
+pub type ResetSyncStateRequest = NetworkMessage<signer::GetRequest>;
+
+/// Transaction History Request
+pub type HistoryRequest = NetworkMessage<HistoryQuery>;
+
+/// Transaction History Export Request
+pub type HistoryExportRequest = NetworkMessage<ExportFormat>;
 
 /// Password Retry Interval
 pub const PASSWORD_RETRY_INTERVAL: Duration = Duration::from_millis(1000);
@@ -88,6 +193,36 @@ pub async fn delay_password_retry() {
     tokio::time::sleep(PASSWORD_RETRY_INTERVAL).await;
 }
 
+/// Waits until `attempts` allow the next password to be checked, telling the `authorizer` how
+/// long it has to wait. A lockout is not waited for, since passwords sent during a lockout are
+/// rejected instead.
+#[inline]
+async fn delay_password_attempt<A>(authorizer: &mut A, attempts: &PasswordAttempts)
+where
+    A: Authorizer,
+{
+    let delay = attempts.remaining();
+    if !delay.is_zero() && !attempts.is_locked_out() {
+        authorizer.password_delay(&attempts.password_delay()).await;
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Returns the path that the unreadable file at `path` is moved to, which is unique to the current
+/// time so that earlier unreadable copies are kept.
+#[inline]
+fn unreadable_path(path: &Path) -> PathBuf {
+    let mut unreadable = OsString::from(path.as_os_str());
+    unreadable.push(format!(
+        ".unreadable-{}",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_millis())
+            .unwrap_or_default()
+    ));
+    unreadable.into()
+}
+
 /// Service Error
 #[derive(Debug)]
 pub enum Error {
@@ -98,7 +233,7 @@ pub enum Error {
     JoinError(JoinError),
 
     /// Failed to Load SDK Parameters
-    ParameterLoadingError,
+    ParameterLoadingError(ParameterError),
 
     /// Save Error
     SaveError(SaveError<File>),
@@ -109,6 +244,85 @@ pub enum Error {
     /// Authorization Error
     AuthorizationError,
 
+    /// Password Lockout Error
+    ///
+    /// Too many wrong passwords were sent, so no password is checked until the lockout ends.
+    PasswordLockout,
+
+    /// Asset Metadata Mismatch Error
+    ///
+    /// The asset metadata in the sign request does not match the signer asset registry, or claims
+    /// the symbol of a registered asset for an unregistered one.
+    AssetMetadataMismatch,
+
+    /// Missing Asset Metadata Error
+    ///
+    /// The sign request did not carry the asset metadata required by the configured
+    /// [`AssetPolicy`].
+    MissingAssetMetadata,
+
+    /// Unknown Account Error
+    ///
+    /// The requested account index does not exist in the wallet.
+    UnknownAccount,
+
+    /// Unsupported Account Error
+    ///
+    /// The signer states only hold the UTXOs of the default account, so other accounts cannot
+    /// sign, synchronize or hand out their addresses yet.
+    UnsupportedAccount,
+
+    /// Invalid Address Error
+    ///
+    /// The given address is not a valid base58-encoded address.
+    InvalidAddress,
+
+    /// Invalid Profile Error
+    ///
+    /// The given profile name is not a valid profile name.
+    InvalidProfile,
+
+    /// Disabled Network Error
+    ///
+    /// The requested network is neither an enabled built-in network nor a configured custom
+    /// network.
+    DisabledNetwork,
+
+    /// Invalid Network Error
+    ///
+    /// A custom network in the [`Config`] does not have a valid name.
+    InvalidNetwork,
+
+    /// State Loading Error
+    ///
+    /// The signer state of a network exists on disk but could not be loaded.
+    StateLoadingError,
+
+    /// State Error
+    ///
+    /// The signer state of the given network could not be loaded or created.
+    StateError(NetworkId, Box<Error>),
+
+    /// Missing Accounts Error
+    ///
+    /// The signer state of the given network does not hold any accounts.
+    MissingAccounts(NetworkId),
+
+    /// Backup Error
+    ///
+    /// The backup of the signer state of the given network could not be restored.
+    BackupError(NetworkId, io::Error),
+
+    /// Backup File Error
+    ///
+    /// A portable backup could not be written or read.
+    BackupFileError(BackupError),
+
+    /// Share Error
+    ///
+    /// The recovery phrase could not be split into shares.
+    ShareError(ShareError),
+
     /// Signer Delay Error
     ///
     /// The signer could not process the request at this time.
@@ -117,6 +331,9 @@ pub enum Error {
 
 from_variant!(Error, AddrParseError, AddrParseError);
 from_variant!(Error, JoinError, JoinError);
+from_variant!(Error, ParameterLoadingError, ParameterError);
+from_variant!(Error, BackupFileError, BackupError);
+from_variant!(Error, ShareError, ShareError);
 from_variant!(Error, SaveError, SaveError<File>);
 from_variant!(Error, Io, io::Error);
 
@@ -127,6 +344,30 @@ impl From<Error> for tide::Error {
             Error::AuthorizationError => {
                 Self::from_str(StatusCode::Unauthorized, "request was not authorized")
             }
+            Error::PasswordLockout => Self::from_str(
+                StatusCode::TooManyRequests,
+                "too many failed password attempts, try again later",
+            ),
+            Error::MissingAssetMetadata => Self::from_str(
+                StatusCode::BadRequest,
+                "asset metadata is required for this transaction",
+            ),
+            Error::AssetMetadataMismatch => Self::from_str(
+                StatusCode::BadRequest,
+                "asset metadata does not match the signer asset registry",
+            ),
+            Error::UnknownAccount => {
+                Self::from_str(StatusCode::NotFound, "the requested account does not exist")
+            }
+            Error::UnsupportedAccount => Self::from_str(
+                StatusCode::BadRequest,
+                "only the default account can sign and synchronize",
+            ),
+            Error::InvalidProfile => Self::from_str(StatusCode::BadRequest, "invalid profile name"),
+            Error::DisabledNetwork => Self::from_str(
+                StatusCode::BadRequest,
+                "the requested network is not enabled in the signer",
+            ),
             Error::Delayed => Self::from_str(
                 StatusCode::Accepted,
                 "another process is currently signing and this request should be tried again later",
@@ -145,72 +386,69 @@ impl Display for Error {
         match self {
             Self::AddrParseError(err) => write!(f, "Address Parse Error: {err}"),
             Self::JoinError(err) => write!(f, "Join Error: {err}"),
-            Self::ParameterLoadingError => write!(f, "Parameter Loading Error"),
+            Self::ParameterLoadingError(err) => write!(f, "Parameter Loading Error: {err}"),
             Self::SaveError(err) => write!(f, "Save Error: {err}"),
             Self::Io(err) => write!(f, "I/O Error: {err}"),
             Self::AuthorizationError => write!(f, "Authorization Error"),
+            Self::PasswordLockout => write!(f, "Password Lockout"),
+            Self::AssetMetadataMismatch => write!(f, "Asset Metadata Mismatch"),
+            Self::MissingAssetMetadata => write!(f, "Missing Asset Metadata"),
+            Self::UnknownAccount => write!(f, "Unknown Account"),
+            Self::UnsupportedAccount => write!(f, "Unsupported Account"),
+            Self::InvalidAddress => write!(f, "Invalid Address"),
+            Self::InvalidProfile => write!(f, "Invalid Profile"),
+            Self::DisabledNetwork => write!(f, "Disabled Network"),
+            Self::InvalidNetwork => write!(f, "Invalid Network"),
+            Self::StateLoadingError => write!(f, "State Loading Error"),
+            Self::StateError(network, err) => write!(f, "State Error on {network}: {err}"),
+            Self::MissingAccounts(network) => write!(f, "Missing Accounts on {network}"),
+            Self::BackupError(network, err) => write!(f, "Backup Error on {network}: {err}"),
+            Self::BackupFileError(err) => write!(f, "Backup File Error: {err}"),
+            Self::ShareError(err) => write!(f, "Share Error: {err}"),
             Self::Delayed => write!(f, "Delay Error"),
         }
     }
 }
 
-/// Result Type
-pub type Result<T, E = Error> = core::result::Result<T, E>;
+impl Error {
+    /// Attributes `self` to the signer state of `network`, unless it already names a network.
+    #[inline]
+    fn in_state(self, network: &NetworkId) -> Self {
+        match self {
+            Self::StateError(..) | Self::MissingAccounts(_) | Self::BackupError(..) => self,
+            err => Self::StateError(network.clone(), Box::new(err)),
+        }
+    }
 
-/// Returns a summary of the current transaction.
-#[inline]
-pub fn display_transaction(
-    transaction: &Transaction,
-    metadata: &AssetMetadata,
-    network: Network,
-) -> String {
-    match transaction {
-        Transaction::ToPrivate(Asset { value, .. }) => match metadata.token_type {
-            TokenType::FT(decimals) => {
-                format!(
-                    "Privatize {} on {} network",
-                    metadata.display(*value, decimals),
-                    network
-                )
-            }
-            TokenType::NFT => {
-                format!("Privatize NFT on {} network", network)
-            }
-        },
-        Transaction::PrivateTransfer(Asset { value, .. }, receiving_key) => {
-            match metadata.token_type {
-                TokenType::FT(decimals) => {
-                    format!(
-                        "Send {} to {} on {} network",
-                        metadata.display(*value, decimals),
-                        address_to_base58(receiving_key),
-                        network
-                    )
-                }
-                TokenType::NFT => {
-                    format!(
-                        "Send NFT to {} on {} network",
-                        address_to_base58(receiving_key),
-                        network
-                    )
-                }
-            }
+    /// Returns the network whose signer state caused `self`, if there is one.
+    #[inline]
+    pub fn network(&self) -> Option<&NetworkId> {
+        match self {
+            Self::StateError(network, _)
+            | Self::MissingAccounts(network)
+            | Self::BackupError(network, _) => Some(network),
+            _ => None,
         }
-        Transaction::ToPublic(Asset { value, .. }, _) => match metadata.token_type {
-            TokenType::FT(decimals) => {
-                format!(
-                    "Public {} on {} network",
-                    metadata.display(*value, decimals),
-                    network
-                )
-            }
-            TokenType::NFT => {
-                format!("Public NFT on {} network", network)
-            }
-        },
+    }
+
+    /// Returns `true` if the signer setup can be tried again after `self`. Configuration and
+    /// parameter errors do not change between attempts so they are not recoverable.
+    #[inline]
+    pub fn is_recoverable(&self) -> bool {
+        !matches!(
+            self,
+            Self::ParameterLoadingError(_)
+                | Self::JoinError(_)
+                | Self::InvalidProfile
+                | Self::InvalidNetwork
+                | Self::DisabledNetwork
+        )
     }
 }
 
+/// Result Type
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
 /// Checked Authorizer
 struct CheckedAuthorizer<A>
 where
@@ -221,6 +459,12 @@ where
 
     /// Authorizer
     authorizer: A,
+
+    /// Password Attempts
+    attempts: PasswordAttempts,
+
+    /// Password Attempts File Path
+    attempts_path: PathBuf,
 }
 
 impl<A> CheckedAuthorizer<A>
@@ -228,19 +472,35 @@ where
     A: Authorizer,
 {
     /// Checks that the authorizer's password matches the known password by sending the `prompt`.
+    ///
+    /// Failed attempts are persisted and delay the next attempt. Once the password is locked out,
+    /// requests are rejected without prompting the authorizer until the lockout ends.
     #[inline]
     async fn check<T>(&mut self, prompt: &T) -> Result<()>
     where
         T: Serialize,
     {
+        if self.attempts.is_locked_out() {
+            warn!(
+                "password is locked out for another {:?}",
+                self.attempts.remaining()
+            )?;
+            return Err(Error::PasswordLockout);
+        }
         self.authorizer.wake(prompt).await;
+        delay_password_attempt(&mut self.authorizer, &self.attempts).await;
         loop {
             if let Some(password) = self.authorizer.password().await.known() {
-                if self
-                    .password_hash
-                    .verify(password.expose_secret().as_bytes())
-                    .is_ok()
+                let password_hash = self.password_hash.clone();
+                if task::spawn_blocking(move || {
+                    password_hash
+                        .verify(password.expose_secret().as_bytes())
+                        .is_ok()
+                })
+                .await?
                 {
+                    self.attempts.record_success();
+                    self.attempts.save(&self.attempts_path).await?;
                     self.authorizer.sleep().await;
                     println!("Password is ok");
                     return Ok(());
@@ -249,7 +509,20 @@ where
                 println!("Password is now known, returning auth error");
                 return Err(Error::AuthorizationError);
             }
-            delay_password_retry().await;
+            self.attempts.record_failure();
+            self.attempts.save(&self.attempts_path).await?;
+            warn!(
+                "wrong password, {} failed attempts in a row",
+                self.attempts.failures
+            )?;
+            if self.attempts.is_locked_out() {
+                self.authorizer
+                    .password_delay(&self.attempts.password_delay())
+                    .await;
+                self.authorizer.sleep().await;
+                return Err(Error::PasswordLockout);
+            }
+            delay_password_attempt(&mut self.authorizer, &self.attempts).await;
         }
     }
 }
@@ -260,10 +533,188 @@ struct State {
     config: Config,
 
     /// Signer
-    signer: NetworkSpecific<Signer>,
+    ///
+    /// Signers are loaded on the first request for their network, so only the enabled networks
+    /// which were used so far are present. The state keeps no other copy of the signer
+    /// parameters, see [`Server::network_parameters`] for how new signers get theirs.
+    signer: HashMap<NetworkId, Signer>,
+
+    /// Wallet Metadata
+    wallet: WalletMetadata,
+
+    /// Wallet Fingerprint
+    ///
+    /// Fingerprint of the key secret of the signer state which unlocked the wallet. Every other
+    /// signer state is checked against it when it is loaded.
+    fingerprint: String,
+
+    /// Transaction History
+    history: HashMap<NetworkId, History>,
+
+    /// Address Book
+    address_book: AddressBook,
+
+    /// Asset Registry
+    asset_registry: AssetRegistry,
+
+    /// Next Authorization Request Id
+    next_request_id: u64,
 
     /// Signing Flag
     currently_signing: bool,
+
+    /// Number of Failed Password Attempts since the Last Successful Login
+    failed_password_attempts: u32,
+}
+
+/// Signing Guard
+///
+/// Clears the signing flag of the state when it is dropped, so that the signer can sign again
+/// however the signing ended.
+struct SigningGuard(Arc<Mutex<State>>);
+
+impl SigningGuard {
+    /// Sets the signing flag of `state`, returning [`Error::Delayed`] if another transaction is
+    /// already being signed.
+    #[inline]
+    fn start(state: &Arc<Mutex<State>>) -> Result<Self> {
+        let mut guard = state.lock();
+        if guard.currently_signing {
+            return Err(Error::Delayed);
+        }
+        guard.currently_signing = true;
+        Ok(Self(state.clone()))
+    }
+}
+
+impl Drop for SigningGuard {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.lock().currently_signing = false;
+    }
+}
+
+impl State {
+    /// Returns the signer for `network` if it has been loaded.
+    #[inline]
+    fn signer(&mut self, network: &NetworkId) -> Result<&mut Signer> {
+        self.signer.get_mut(network).ok_or(Error::DisabledNetwork)
+    }
+
+    /// Returns the transaction history for `network`.
+    #[inline]
+    fn history(&mut self, network: &NetworkId) -> &mut History {
+        self.history.entry(network.clone()).or_default()
+    }
+
+    /// Returns a network whose signer has been loaded, preferring the enabled networks in their
+    /// configured order.
+    #[inline]
+    fn loaded_network(&self) -> Result<NetworkId> {
+        self.config
+            .enabled()
+            .into_iter()
+            .find(|network| self.signer.contains_key(network))
+            .ok_or(Error::DisabledNetwork)
+    }
+
+    /// Returns the proving directory of `network` if it is a custom network with its own proving
+    /// contexts.
+    #[inline]
+    fn custom_proving_directory(&self, network: &NetworkId) -> Option<PathBuf> {
+        match network {
+            NetworkId::Custom(name) => self
+                .config
+                .custom_network(name)
+                .and_then(|network| network.proving_directory.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the key secret of the wallet, taken from any loaded signer which matches the
+    /// wallet fingerprint.
+    #[inline]
+    fn key_secret(&self) -> Option<TestnetKeySecret> {
+        self.signer
+            .values()
+            .filter_map(|signer| Some(signer.state().accounts().as_ref()?.keys().clone()))
+            .find(|keys| wallet::fingerprint(keys) == self.fingerprint)
+    }
+}
+
+/// Parameter Loading Task
+///
+/// Loads the signer parameters in the background so that decoding the proving contexts overlaps
+/// with the user going through the setup and typing their password.
+struct ParameterTask {
+    /// Proving Directory
+    proving_directory: PathBuf,
+
+    /// Proving Context Cache Directory
+    cache_directory: Option<PathBuf>,
+
+    /// Loading Task
+    task: Option<JoinHandle<Result<SignerParameters, ParameterError>>>,
+
+    /// Loaded Parameters
+    parameters: Option<SignerParameters>,
+}
+
+impl ParameterTask {
+    /// Starts loading the signer parameters from the proving contexts in `proving_directory`,
+    /// using the proving context cache in `cache_directory` if it is set.
+    #[inline]
+    fn spawn(proving_directory: PathBuf, cache_directory: Option<PathBuf>) -> Self {
+        let mut task = Self {
+            proving_directory,
+            cache_directory,
+            task: None,
+            parameters: None,
+        };
+        task.respawn();
+        task
+    }
+
+    /// Starts loading the signer parameters again.
+    #[inline]
+    fn respawn(&mut self) {
+        let proving_directory = self.proving_directory.clone();
+        let cache_directory = self.cache_directory.clone();
+        self.task = Some(task::spawn_blocking(move || {
+            crate::parameters::load(proving_directory, cache_directory.as_deref())
+        }));
+    }
+
+    /// Waits for the signer parameters to be loaded and returns them, loading them again if they
+    /// were already [taken](Self::take).
+    #[inline]
+    async fn get(&mut self) -> Result<&SignerParameters> {
+        if self.parameters.is_none() {
+            if self.task.is_none() {
+                self.respawn();
+            }
+            if let Some(task) = self.task.take() {
+                let started = Instant::now();
+                self.parameters = Some(task.await??);
+                info!("waited {:?} for parameters", started.elapsed())?;
+            }
+        }
+        Ok(self
+            .parameters
+            .as_ref()
+            .expect("The parameters are stored whenever the loading task succeeds."))
+    }
+
+    /// Waits for the signer parameters to be loaded and moves them out of `self`, so that the
+    /// signer built from them holds the only copy.
+    #[inline]
+    async fn take(&mut self) -> Result<SignerParameters> {
+        self.get().await?;
+        Ok(self
+            .parameters
+            .take()
+            .expect("The parameters are stored whenever the loading task succeeds."))
+    }
 }
 
 /// Signer Server
@@ -278,6 +729,12 @@ where
 
     /// Authorizer
     authorizer: Arc<AsyncMutex<CheckedAuthorizer<A>>>,
+
+    /// Signer Loading Locks
+    ///
+    /// Serializes the lazy loading of each signer so that it is only loaded once, while signers
+    /// of different networks are loaded concurrently.
+    loading: Arc<Mutex<HashMap<NetworkId, Arc<AsyncMutex<()>>>>>,
 }
 
 impl<A> Server<A>
@@ -285,208 +742,676 @@ where
     A: Authorizer,
 {
     /// Builds a new [`Server`] from `config` and `authorizer`.
+    ///
+    /// Failures while setting up the signer states are reported to the `authorizer`, which can
+    /// choose how to recover from them. See [`Authorizer::setup_failure`] for more.
     #[inline]
-    pub async fn build(config: Config, mut authorizer: A) -> Result<Self> {
+    pub async fn build(mut config: Config, mut authorizer: A) -> Result<Self> {
         info!("building signer server")?;
+        let profiles = config.profiles().await?;
+        if let Some(profile) = authorizer
+            .select_profile(profiles, config.profile.clone())
+            .await
+        {
+            if profile != config.profile {
+                config = config.with_profile(&profile).ok_or(Error::InvalidProfile)?;
+                config.save_selected_profile().await?;
+            }
+        }
+        info!("using profile: {}", config.profile)?;
         info!("loading latest parameters from Manta Parameters")?;
-        let data_path = config.data_directory().to_owned();
-        let parameters = task::spawn_blocking(move || crate::parameters::load(data_path))
-            .await?
-            .ok_or(Error::ParameterLoadingError)?;
+        let mut parameter_task = ParameterTask::spawn(
+            config.proving_directory.clone(),
+            config.proving_cache_directory.clone(),
+        );
         info!("setting up configuration")?;
-        let backup_exists = config
-            .check_all_backups()
+        if let Some(network) = config
+            .custom_networks
+            .iter()
+            .find(|network| !network::is_valid_custom_name(&network.name))
+        {
+            warn!("invalid custom network name: {:?}", network.name)?;
+            return Err(Error::InvalidNetwork);
+        }
+        let first_network = match config.enabled().into_iter().next() {
+            Some(network) => network,
+            _ => {
+                warn!("no networks are enabled in the signer configuration")?;
+                return Err(Error::DisabledNetwork);
+            }
+        };
+        let mut recover = false;
+        let (password_hash, state) = loop {
+            let err = match Self::setup(
+                &config,
+                &mut authorizer,
+                &mut parameter_task,
+                &first_network,
+                recover,
+            )
             .await
-            .expect("Unable to check for the existence of one more more backup files");
-        if backup_exists {
-            info!("backup file found, restoring backup.")?;
-        }
-        let data_exists = config.does_data_exist().await;
-        let does_all_data_exist = data_exists.dolphin && data_exists.calamari && data_exists.manta;
-        let does_one_data_exist = data_exists.dolphin || data_exists.calamari || data_exists.manta;
-        let setup = authorizer.setup(does_one_data_exist).await;
-        let (password_hash, dolphin_signer, calamari_signer, manta_signer) = match setup {
-            Setup::CreateAccount(mnemonic) => loop {
-                if let Some((_password, password_hash)) = Self::load_password(&mut authorizer).await
-                {
-                    info!("creating dolphin state.")?;
-                    let dolphin_state = Self::create_state(
-                        &config.data_path.dolphin,
-                        &password_hash,
-                        mnemonic.clone(),
-                        &parameters,
-                    )
-                    .await?;
-
-                    info!("creating calamari state.")?;
-                    let calamari_state = Self::create_state(
-                        &config.data_path.calamari,
-                        &password_hash,
-                        mnemonic.clone(),
-                        &parameters,
-                    )
-                    .await?;
-
-                    info!("creating manta state.")?;
-                    let manta_state = Self::create_state(
-                        &config.data_path.manta,
-                        &password_hash,
-                        mnemonic.clone(),
-                        &parameters,
-                    )
-                    .await?;
-                    break (
-                        password_hash,
-                        Signer::from_parts(parameters.clone(), dolphin_state),
-                        Signer::from_parts(parameters.clone(), calamari_state),
-                        Signer::from_parts(parameters.clone(), manta_state),
-                    );
+            {
+                Ok(setup) => break setup,
+                Err(err) if !err.is_recoverable() => return Err(err),
+                Err(err) => err,
+            };
+            recover = false;
+            warn!("unable to set up the signer: {err}")?;
+            let network = err.network().cloned();
+            let backup_exists = match &network {
+                Some(network) => fs::metadata(config.backup_data_file(network)).await.is_ok(),
+                _ => false,
+            };
+            let failure = SetupFailure {
+                network: network.clone(),
+                error: err.to_string(),
+                backup_exists,
+            };
+            match (authorizer.setup_failure(&failure).await, network) {
+                (RecoveryChoice::Retry, _) => info!("retrying signer setup.")?,
+                (RecoveryChoice::RestoreBackup, Some(network)) if backup_exists => {
+                    info!("restoring {} state from its backup.", network)?;
+                    config
+                        .check_for_backup(&network)
+                        .await
+                        .map_err(|err| Error::BackupError(network, err))?;
                 }
-                delay_password_retry().await;
-            },
-            Setup::Login => loop {
-                if let Some((_, password_hash)) = Self::load_password(&mut authorizer).await {
-                    let mut recovery_mnemonic: Option<Mnemonic> = None;
-                    if !does_all_data_exist {
-                        // Edge case: 1 or 2 out of 3 storage.dat files are missing.
-                        // We need to get the mnemonic from one of the existing files
-                        // and use it to recreate the missing files.
-                        info!("not all data files were found, starting re-creation process from existing file's seed phrase.")?;
-                        recovery_mnemonic = Self::get_mnemonic_for_missing_file(
-                            data_exists,
-                            &config,
-                            &password_hash,
-                            parameters.clone(),
-                        )
-                        .await;
-                    }
-
-                    let dolphin_state = Self::create_or_load_state(
-                        !data_exists.dolphin,
-                        &config.data_path.dolphin,
-                        &password_hash,
-                        recovery_mnemonic.clone(),
-                        &parameters,
-                    )
-                    .await?;
-
-                    let calamari_state = Self::create_or_load_state(
-                        !data_exists.calamari,
-                        &config.data_path.calamari,
-                        &password_hash,
-                        recovery_mnemonic.clone(),
-                        &parameters,
-                    )
-                    .await?;
-
-                    let manta_state = Self::create_or_load_state(
-                        !data_exists.manta,
-                        &config.data_path.manta,
-                        &password_hash,
-                        recovery_mnemonic.clone(),
-                        &parameters,
-                    )
-                    .await?;
-
-                    if let (Some(dolphin_state), Some(calamari_state), Some(manta_state)) =
-                        (dolphin_state, calamari_state, manta_state)
-                    {
-                        break (
-                            password_hash,
-                            Signer::from_parts(parameters.clone(), dolphin_state),
-                            Signer::from_parts(parameters.clone(), calamari_state),
-                            Signer::from_parts(parameters.clone(), manta_state),
-                        );
-                    }
+                (RecoveryChoice::RecoverFromMnemonic, _) => {
+                    info!("recovering the signer from its recovery phrase.")?;
+                    recover = true;
                 }
-                delay_password_retry().await;
-            },
+                (RecoveryChoice::DeleteNetwork, Some(network)) => {
+                    warn!("deleting {} state.", network)?;
+                    Self::remove_state(&config, &network).await?;
+                }
+                (RecoveryChoice::Abort, _) => return Err(err),
+                (choice, _) => warn!(
+                    "{:?} does not apply to this failure, retrying instead.",
+                    choice
+                )?,
+            }
         };
         info!("telling authorizer to sleep")?;
         authorizer.sleep().await;
+        let attempts = PasswordAttempts::load(&config.password_attempts_path).await?;
         Ok(Self {
-            state: Arc::new(Mutex::new(State {
-                config,
-                signer: NetworkSpecific {
-                    dolphin: dolphin_signer,
-                    calamari: calamari_signer,
-                    manta: manta_signer,
-                },
-                currently_signing: false,
-            })),
+            state: Arc::new(Mutex::new(state)),
             authorizer: Arc::new(AsyncMutex::new(CheckedAuthorizer {
                 password_hash,
                 authorizer,
+                attempts,
+                attempts_path: config.password_attempts_path.clone(),
             })),
+            loading: Default::default(),
         })
     }
 
-    /// If one of the network storage files is missing, we need to load an existing network
-    /// state and use it's recovery phrase to re-create the missing file. This function
-    /// returns the seed phrase from an existing state file.
+    /// Sets up the signer states for `config`, unlocking them with a password from `authorizer`.
+    /// If `recover` is set, the authorizer is asked to create or recover an account even if there
+    /// are signer states on disk.
     #[inline]
-    async fn get_mnemonic_for_missing_file(
-        data_exists: DataExistenceResponse,
+    async fn setup(
         config: &Config,
-        password_hash: &PasswordHash<Argon2>,
-        parameters: SignerParameters,
-    ) -> Option<Mnemonic> {
-        let existing_state_path = if data_exists.dolphin {
-            &config.data_path.dolphin
-        } else if data_exists.calamari {
-            &config.data_path.calamari
-        } else {
-            &config.data_path.manta
-        };
-        let existing_signer = Signer::from_parts(
-            parameters.clone(),
-            Self::load_state(existing_state_path, password_hash, &parameters)
+        authorizer: &mut A,
+        parameter_task: &mut ParameterTask,
+        first_network: &NetworkId,
+        recover: bool,
+    ) -> Result<(PasswordHash<Argon2>, State)> {
+        for network in config.networks() {
+            if config
+                .check_for_backup(&network)
                 .await
-                .expect("Unable to get dolphin state")?,
+                .map_err(|err| Error::BackupError(network.clone(), err))?
+            {
+                info!("backup file found, restored {} backup.", network)?;
+            }
+        }
+        fs::create_dir_all(config.data_directory()).await?;
+        Self::finish_rekey(config).await?;
+        let mut existing_networks = Vec::new();
+        for network in config.networks() {
+            if fs::metadata(config.data_file(&network)).await.is_ok() {
+                existing_networks.push(network);
+            }
+        }
+        let setup = authorizer
+            .setup(!recover && !existing_networks.is_empty())
+            .await;
+        let is_new_account = matches!(
+            setup,
+            Setup::CreateAccount { .. } | Setup::ImportBackup { .. }
         );
-        Some(
-            existing_signer
-                .state()
-                .accounts()
-                .as_ref()
-                .expect("No accounts in signer state!")
-                .keys()
-                .expose_mnemonic()
-                .clone(),
-        )
-    }
+        let mut signer = HashMap::new();
+        let mut failed_password_attempts = 0;
+        let (password_hash, wallet, fingerprint) = match setup {
+            Setup::CreateAccount {
+                mnemonic,
+                passphrase,
+            } => {
+                let header = Self::calibrate_kdf_header().await?;
+                loop {
+                    if let Some((_password, password_hash)) =
+                        Self::load_password(authorizer, &header).await
+                    {
+                        let parameters = parameter_task.take().await?;
+                        header.save(&config.kdf_header_path).await?;
+                        let wallet = WalletMetadata::new(passphrase.clone());
+                        let keys = wallet.key_secret(mnemonic.clone());
+                        let fingerprint = wallet::fingerprint(&keys);
 
-    /// Recreates a new state from a mnemonic of an existing state
-    /// if file was deleted or loads an existing state during login.
-    #[inline]
-    async fn create_or_load_state(
-        should_recreate: bool,
-        data_path: &Path,
-        password_hash: &PasswordHash<Argon2>,
-        recovery_mnemonic: Option<Mnemonic>,
-        parameters: &SignerParameters,
-    ) -> Result<Option<SignerState>> {
-        if should_recreate {
-            info!("state missing! recreating state.")?;
-            let state = Self::create_state(
-                data_path,
-                password_hash,
-                recovery_mnemonic.expect("unable to retrieve mnemonic for account recreation."),
-                parameters,
-            )
-            .await
-            .expect("Unable to recreate signer instance from existing mnemonic.");
-            Ok(Some(state))
-        } else {
-            Self::load_state(data_path, password_hash, parameters).await
-        }
-    }
+                        // NOTE: Only the state of the first enabled network is created here. The
+                        //       states of the other networks are created from the same keys on
+                        //       their first request, so any state left over from a previous
+                        //       account is removed.
+                        info!("creating {} state.", first_network)?;
+                        let state = Self::create_state(
+                            &config.data_file(first_network),
+                            &password_hash,
+                            keys,
+                            &parameters,
+                        )
+                        .await
+                        .map_err(|err| err.in_state(first_network))?;
+                        signer.insert(first_network.clone(), Self::new_signer(parameters, state));
+                        for network in config.networks() {
+                            if &network != first_network {
+                                Self::remove_state(config, &network)
+                                    .await
+                                    .map_err(|err| err.in_state(&network))?;
+                            }
+                        }
 
-    /// If users cancels a transaction, this method will be called by front-end to indicate that
-    /// the signer can now start signing new transactions.
-    #[inline]
-    pub async fn cancel_signing(&mut self) {
-        self.state.lock().currently_signing = false;
+                        info!("saving wallet metadata.")?;
+                        Self::save_encrypted(&config.wallet_path, &password_hash, wallet.clone())
+                            .await?;
+                        break (password_hash, Some(wallet), fingerprint);
+                    }
+                    delay_password_retry().await;
+                }
+            }
+            Setup::ImportBackup { path, password } => {
+                info!("reading backup from {:?}.", path)?;
+                let file = fs::read(&path).await?;
+                let backup = task::spawn_blocking(move || {
+                    Backup::decrypt(&file, password.expose_secret().as_bytes())
+                })
+                .await??;
+                let header = Self::calibrate_kdf_header().await?;
+                loop {
+                    if let Some((_, password_hash)) = Self::load_password(authorizer, &header).await
+                    {
+                        let parameters = parameter_task.take().await?;
+                        let (wallet, fingerprint) = Self::restore_backup(
+                            config,
+                            &header,
+                            &password_hash,
+                            backup,
+                            parameters,
+                            first_network,
+                            &mut signer,
+                        )
+                        .await?;
+                        break (password_hash, Some(wallet), fingerprint);
+                    }
+                    delay_password_retry().await;
+                }
+            }
+            Setup::Login => {
+                // NOTE: One existing state is loaded to check the password, preferring an enabled
+                //       network so that its signer can be kept.
+                let existing_network = existing_networks
+                    .iter()
+                    .find(|network| config.is_enabled(network))
+                    .or_else(|| existing_networks.first())
+                    .cloned()
+                    .unwrap_or_else(|| first_network.clone());
+                let mut attempts = PasswordAttempts::load(&config.password_attempts_path).await?;
+                let header = KdfHeader::load(&config.kdf_header_path).await?;
+                let (password, password_hash, fingerprint) = loop {
+                    delay_password_attempt(authorizer, &attempts).await;
+                    if let Some((password, password_hash)) =
+                        Self::load_password(authorizer, &header).await
+                    {
+                        // NOTE: The signer cannot be used at all without logging in, so a locked
+                        //       out password fails each attempt and the login waits for the next.
+                        if attempts.is_locked_out() {
+                            warn!(
+                                "password is locked out for another {:?}",
+                                attempts.remaining()
+                            )?;
+                            authorizer.password_delay(&attempts.password_delay()).await;
+                            continue;
+                        }
+                        let loaded = Self::load_state(
+                            &config.data_file(&existing_network),
+                            &password_hash,
+                            parameter_task.get().await?,
+                        )
+                        .await
+                        .map_err(|err| err.in_state(&existing_network))?;
+                        if let Some(state) = loaded {
+                            let keys = state
+                                .accounts()
+                                .as_ref()
+                                .ok_or_else(|| Error::MissingAccounts(existing_network.clone()))?
+                                .keys()
+                                .clone();
+                            let fingerprint = wallet::fingerprint(&keys);
+                            info!(
+                                "unlocked wallet {} with {} state.",
+                                fingerprint, existing_network
+                            )?;
+                            attempts.record_success();
+                            failed_password_attempts = attempts.take_unreported();
+                            attempts.save(&config.password_attempts_path).await?;
+                            let parameters = parameter_task.take().await?;
+                            if config.is_enabled(&existing_network) {
+                                signer
+                                    .insert(existing_network, Self::new_signer(parameters, state));
+                            } else {
+                                info!(
+                                    "only disabled network states were found, creating {} state from their keys.",
+                                    first_network
+                                )?;
+                                let state = Self::create_state(
+                                    &config.data_file(first_network),
+                                    &password_hash,
+                                    keys,
+                                    &parameters,
+                                )
+                                .await
+                                .map_err(|err| err.in_state(first_network))?;
+                                signer.insert(
+                                    first_network.clone(),
+                                    Self::new_signer(parameters, state),
+                                );
+                            }
+                            break (password, password_hash, fingerprint);
+                        }
+                        attempts.record_failure();
+                        attempts.save(&config.password_attempts_path).await?;
+                        warn!(
+                            "wrong password, {} failed attempts in a row",
+                            attempts.failures
+                        )?;
+                        if attempts.is_locked_out() {
+                            authorizer.password_delay(&attempts.password_delay()).await;
+                        }
+                    } else {
+                        delay_password_retry().await;
+                    }
+                };
+                if header.is_weak() {
+                    info!("re-keying the signer files with stronger key derivation parameters.")?;
+                    match Self::rekey(config, &password_hash, password).await {
+                        Ok(password_hash) => (password_hash, None, fingerprint),
+                        Err(err) => {
+                            warn!(
+                                "unable to re-key the signer files, keeping the current key: {err}"
+                            )?;
+                            (password_hash, None, fingerprint)
+                        }
+                    }
+                } else {
+                    (password_hash, None, fingerprint)
+                }
+            }
+        };
+        // NOTE: The auxiliary files are independent of each other, so they are decrypted
+        //       concurrently to shorten the time between entering the password and being ready.
+        let (wallet, history, address_book) = try_join3(
+            async {
+                match wallet {
+                    Some(wallet) => Ok(wallet),
+                    _ => {
+                        info!("loading wallet metadata")?;
+                        Self::load_encrypted(&config.wallet_path, &password_hash).await
+                    }
+                }
+            },
+            async {
+                if is_new_account {
+                    return Ok(HashMap::new());
+                }
+                info!("loading transaction history")?;
+                try_join_all(config.enabled().into_iter().map(|network| {
+                    let path = config.history_file(&network);
+                    let password_hash = &password_hash;
+                    async move {
+                        Ok::<_, Error>((
+                            network,
+                            Self::load_encrypted::<History>(&path, password_hash).await?,
+                        ))
+                    }
+                }))
+                .await
+                .map(|history| history.into_iter().collect::<HashMap<_, History>>())
+            },
+            async {
+                info!("loading address book")?;
+                Self::load_encrypted(&config.address_book_path, &password_hash).await
+            },
+        )
+        .await?;
+        info!("loading asset registry")?;
+        let asset_registry = match AssetRegistry::load(&config.asset_registry_path).await {
+            Ok(asset_registry) => asset_registry,
+            Err(err) => {
+                warn!("unable to load custom assets, using bundled assets only: {err}")?;
+                AssetRegistry::default()
+            }
+        };
+        Ok((
+            password_hash,
+            State {
+                config: config.clone(),
+                signer,
+                wallet,
+                fingerprint,
+                history,
+                address_book,
+                asset_registry,
+                next_request_id: 0,
+                currently_signing: false,
+                failed_password_attempts,
+            },
+        ))
+    }
+
+    /// Restores the wallet in `backup` under the new `password_hash` derived with `header`,
+    /// replacing every encrypted file on disk and loading the signer for `first_network` into
+    /// `signer`.
+    ///
+    /// Signer states in the backup which were not built from its mnemonic are dropped, and
+    /// `first_network` is recreated from the mnemonic if the backup has no state for it. Like
+    /// [`rekey`], the restored files are first written next to the files they replace and moving
+    /// the new key derivation header into place commits them, so a failed restore leaves the
+    /// previous wallet as it was. The transaction histories, leftover state backups and failed
+    /// password attempts of the previous wallet are removed with it.
+    ///
+    /// [`rekey`]: Self::rekey
+    #[inline]
+    async fn restore_backup(
+        config: &Config,
+        header: &KdfHeader,
+        password_hash: &PasswordHash<Argon2>,
+        backup: Backup,
+        parameters: SignerParameters,
+        first_network: &NetworkId,
+        signer: &mut HashMap<NetworkId, Signer>,
+    ) -> Result<(WalletMetadata, String)> {
+        let keys = backup.wallet.key_secret(backup.mnemonic);
+        let fingerprint = wallet::fingerprint(&keys);
+        info!("restoring wallet {} from backup.", fingerprint)?;
+        let pending_header = kdf::pending_path(&config.kdf_header_path);
+        let result = async {
+            header.save(&pending_header).await?;
+            let networks = config.networks();
+            let mut first_state = None;
+            let mut restored = Vec::new();
+            for (name, state) in backup.states {
+                let network = NetworkId::from_name(&name);
+                if !networks.contains(&network) {
+                    warn!("skipping {} state which is not configured.", network)?;
+                    continue;
+                }
+                let found = state
+                    .accounts()
+                    .as_ref()
+                    .map(|accounts| wallet::fingerprint(accounts.keys()));
+                if found.as_ref() != Some(&fingerprint) {
+                    warn!(
+                        "skipping {} state which does not belong to the backed up wallet.",
+                        network
+                    )?;
+                    continue;
+                }
+                info!("restoring {} state.", network)?;
+                Self::save_encrypted(
+                    &kdf::pending_path(&config.data_file(&network)),
+                    password_hash,
+                    state.clone(),
+                )
+                .await
+                .map_err(|err| err.in_state(&network))?;
+                if &network == first_network {
+                    first_state = Some(state);
+                }
+                restored.push(network);
+            }
+            let first_state = match first_state {
+                Some(state) => state,
+                _ => {
+                    info!("backup has no {} state, creating it.", first_network)?;
+                    restored.push(first_network.clone());
+                    Self::create_state(
+                        &kdf::pending_path(&config.data_file(first_network)),
+                        password_hash,
+                        keys,
+                        &parameters,
+                    )
+                    .await
+                    .map_err(|err| err.in_state(first_network))?
+                }
+            };
+
+            // NOTE: The files of the replaced wallet are encrypted under the old key and belong to
+            //       another wallet, so they are removed when the restore is committed.
+            for network in networks {
+                if !restored.contains(&network) {
+                    Self::mark_removed(&config.data_file(&network)).await?;
+                }
+                Self::mark_removed(&config.backup_data_file(&network)).await?;
+                Self::mark_removed(&config.history_file(&network)).await?;
+            }
+            info!("saving wallet metadata and address book.")?;
+            Self::save_encrypted(
+                &kdf::pending_path(&config.wallet_path),
+                password_hash,
+                backup.wallet.clone(),
+            )
+            .await?;
+            Self::save_encrypted(
+                &kdf::pending_path(&config.address_book_path),
+                password_hash,
+                backup.address_book,
+            )
+            .await?;
+            fs::rename(&pending_header, &config.kdf_header_path).await?;
+            Ok(first_state)
+        }
+        .await;
+        Self::finish_rekey(config).await?;
+        let first_state = result?;
+        signer.insert(
+            first_network.clone(),
+            Self::new_signer(parameters, first_state),
+        );
+        if fs::metadata(&config.password_attempts_path).await.is_ok() {
+            fs::remove_file(&config.password_attempts_path).await?;
+        }
+        Ok((backup.wallet, fingerprint))
+    }
+
+    /// Marks the file at `path` for removal when a re-key or restore is committed.
+    #[inline]
+    async fn mark_removed(path: &Path) -> Result<()> {
+        Ok(fs::write(kdf::pending_path(path), []).await?)
+    }
+
+    /// Removes the signer state and its backup for `network` from disk, if they exist.
+    #[inline]
+    async fn remove_state(config: &Config, network: &NetworkId) -> Result<()> {
+        for path in [config.data_file(network), config.backup_data_file(network)] {
+            if fs::metadata(&path).await.is_ok() {
+                fs::remove_file(&path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the signer for `network` if it has not been loaded yet, recreating its state from the
+    /// keys of an already loaded signer if the state file is missing.
+    #[inline]
+    async fn load_signer(&self, network: &NetworkId) -> Result<()> {
+        let loading = self
+            .loading
+            .lock()
+            .entry(network.clone())
+            .or_default()
+            .clone();
+        let _loading = loading.lock().await;
+        let (path, keys) = {
+            let state = self.state.lock();
+            if !state.config.is_enabled(network) {
+                return Err(Error::DisabledNetwork);
+            }
+            if state.signer.contains_key(network) {
+                return Ok(());
+            }
+            (state.config.data_file(network), state.key_secret())
+        };
+        let parameters = self.network_parameters(network).await?;
+        let password_hash = self.authorizer.lock().await.password_hash.clone();
+        let signer_state = if fs::metadata(&path).await.is_ok() {
+            info!("loading {} state.", network)?;
+            let signer_state = Self::load_state(&path, &password_hash, &parameters)
+                .await?
+                .ok_or(Error::StateLoadingError)?;
+            match self.check_divergence(network, &signer_state).await? {
+                DivergenceChoice::Keep => signer_state,
+                DivergenceChoice::Repair => {
+                    let diverged_path = path.with_extension("diverged");
+                    info!(
+                        "moving diverging {} state to {:?} and recreating it.",
+                        network, diverged_path
+                    )?;
+                    fs::rename(&path, &diverged_path).await?;
+                    Self::create_state(
+                        &path,
+                        &password_hash,
+                        keys.ok_or(Error::StateLoadingError)?,
+                        &parameters,
+                    )
+                    .await?
+                }
+            }
+        } else {
+            info!("{} state missing! recreating state.", network)?;
+            Self::create_state(
+                &path,
+                &password_hash,
+                keys.ok_or(Error::StateLoadingError)?,
+                &parameters,
+            )
+            .await?
+        };
+        self.state
+            .lock()
+            .signer
+            .insert(network.clone(), Self::new_signer(parameters, signer_state));
+        Ok(())
+    }
+
+    /// Checks that `signer_state` of `network` was built from the wallet mnemonic, asking the
+    /// authorizer what to do if it was not.
+    ///
+    /// States which match the wallet are always kept.
+    #[inline]
+    async fn check_divergence(
+        &self,
+        network: &NetworkId,
+        signer_state: &SignerState,
+    ) -> Result<DivergenceChoice> {
+        let found = wallet::fingerprint(
+            signer_state
+                .accounts()
+                .as_ref()
+                .ok_or(Error::StateLoadingError)?
+                .keys(),
+        );
+        let expected = self.state.lock().fingerprint.clone();
+        if found == expected {
+            return Ok(DivergenceChoice::Keep);
+        }
+        warn!(
+            "{} state belongs to wallet {} instead of wallet {}.",
+            network, found, expected
+        )?;
+        let divergence = StateDivergence {
+            network: network.clone(),
+            expected,
+            found,
+        };
+        let choice = self
+            .authorizer
+            .lock()
+            .await
+            .authorizer
+            .state_divergence(&divergence)
+            .await;
+        if choice == DivergenceChoice::Keep {
+            warn!("keeping diverging {} state for this session.", network)?;
+        }
+        Ok(choice)
+    }
+
+    /// Returns the signer parameters for a new signer on `network`.
+    ///
+    /// # Implementation Note
+    ///
+    /// [`Signer`] owns its parameters, so they cannot be shared between signers and every loaded
+    /// network holds one copy of them. No other copy is kept: the parameters are cloned from a
+    /// loaded signer which uses the same proving contexts, and are only decoded again if there is
+    /// none.
+    #[inline]
+    async fn network_parameters(&self, network: &NetworkId) -> Result<SignerParameters> {
+        let (custom_proving_directory, config) = {
+            let state = self.state.lock();
+            let custom_proving_directory = state.custom_proving_directory(network);
+            if let Some(signer) = state.signer.iter().find_map(|(other, signer)| {
+                (state.custom_proving_directory(other) == custom_proving_directory)
+                    .then_some(signer)
+            }) {
+                return Ok(signer.parameters().clone());
+            }
+            (custom_proving_directory, state.config.clone())
+        };
+        info!("loading {} parameters.", network)?;
+        Ok(task::spawn_blocking(move || {
+            let cache_directory = config.proving_cache_directory.as_deref();
+            match custom_proving_directory {
+                Some(proving_directory) => crate::parameters::load_from_proving_directory(
+                    proving_directory,
+                    cache_directory,
+                ),
+                _ => crate::parameters::load(&config.proving_directory, cache_directory),
+            }
+        })
+        .await??)
+    }
+
+    /// Builds a new [`Signer`] from the decoded `parameters` and `state`.
+    #[inline]
+    fn new_signer(parameters: SignerParameters, state: SignerState) -> Signer {
+        Signer::from_parts(parameters, state)
+    }
+
+    /// Returns an error if `network` is not one of the enabled networks.
+    #[inline]
+    fn check_enabled(&self, network: &NetworkId) -> Result<()> {
+        if self.state.lock().config.is_enabled(network) {
+            Ok(())
+        } else {
+            Err(Error::DisabledNetwork)
+        }
+    }
+
+    /// If users cancels a transaction, this method will be called by front-end to indicate that
+    /// the signer can now start signing new transactions.
+    #[inline]
+    pub async fn cancel_signing(&mut self) {
+        self.state.lock().currently_signing = false;
         // Forcefully sleep because the authorizer gets stuck awake if we exit recovery window
         self.authorizer.lock().await.authorizer.sleep();
     }
@@ -510,49 +1435,227 @@ where
         api.at("/version")
             .get(|_| http::into_body(Server::<A>::version));
         http::register_post(&mut api, "/sync", Server::sync);
-        http::register_post(&mut api, "/sign", Server::sign);
+        http::register_post_with_origin(&mut api, "/sign", Server::sign_with_origin);
         http::register_post(&mut api, "/address", Server::address);
+        http::register_post(&mut api, "/balances", Server::balances);
+        http::register_post(&mut api, "/history", Server::history);
+        http::register_post(&mut api, "/history/export", Server::export_history);
+        http::register_post(&mut api, "/reset_sync_state", Server::reset_sync_state);
         info!("serving signer API at {}", socket_address)?;
         api.listen(socket_address).await?;
         Ok(())
     }
 
-    /// Loads the password from the `authorizer` and compute the password hash.
+    /// Loads the password from the `authorizer` and computes the password hash with the key
+    /// derivation parameters in `header`.
     #[inline]
-    async fn load_password(authorizer: &mut A) -> Option<(SecretString, PasswordHash<Argon2>)> {
+    async fn load_password(
+        authorizer: &mut A,
+        header: &KdfHeader,
+    ) -> Option<(SecretString, PasswordHash<Argon2>)> {
         info!("loading password from authorizer").ok()?;
         let password = authorizer.password().await.known()?;
-        let password_hash = PasswordHash::from_default(password.expose_secret().as_bytes());
-        Some((password, password_hash))
+        let header = header.clone();
+        task::spawn_blocking(move || {
+            let password_hash =
+                PasswordHash::from_header(&header, password.expose_secret().as_bytes());
+            (password, password_hash)
+        })
+        .await
+        .ok()
+    }
+
+    /// Builds a key derivation header for a new account, calibrated on this machine.
+    #[inline]
+    async fn calibrate_kdf_header() -> Result<KdfHeader> {
+        info!("calibrating key derivation parameters")?;
+        let header = task::spawn_blocking(KdfHeader::calibrate).await?;
+        info!(
+            "using {} KiB of memory and {} passes for key derivation",
+            header.memory_cost, header.time_cost
+        )?;
+        Ok(header)
     }
 
-    /// Creates the initial signer state for a new account.
+    /// Re-encrypts every encrypted file of `config` under a key derived from `password` with
+    /// newly calibrated parameters, returning the new password hash.
+    ///
+    /// The new key derivation header and the re-encrypted files are first written next to the
+    /// files they replace, and moving the new header into place commits the re-key, so that
+    /// [`finish_rekey`] can complete or roll back a re-key which was interrupted. Signer states
+    /// which cannot be decrypted abort the re-key, and state backups which cannot be decrypted are
+    /// removed so that they are not restored over the re-keyed states. Auxiliary files which
+    /// cannot be decrypted are left as they are, to be moved aside when they are loaded.
+    ///
+    /// [`finish_rekey`]: Self::finish_rekey
+    #[inline]
+    async fn rekey(
+        config: &Config,
+        password_hash: &PasswordHash<Argon2>,
+        password: SecretString,
+    ) -> Result<PasswordHash<Argon2>> {
+        let header = Self::calibrate_kdf_header().await?;
+        let new_header = header.clone();
+        let new_password_hash = task::spawn_blocking(move || {
+            PasswordHash::from_header(&new_header, password.expose_secret().as_bytes())
+        })
+        .await?;
+        let pending_header = kdf::pending_path(&config.kdf_header_path);
+        let result = async {
+            header.save(&pending_header).await?;
+            for network in config.networks() {
+                let path = config.data_file(&network);
+                if fs::metadata(&path).await.is_ok()
+                    && !Self::reencrypt::<SignerState>(&path, password_hash, &new_password_hash)
+                        .await?
+                {
+                    return Err(Error::StateLoadingError.in_state(&network));
+                }
+                // NOTE: A state backup left over from an interrupted save replaces the state on
+                //       the next start, so it has to be readable under the new key as well.
+                let backup_path = config.backup_data_file(&network);
+                if !Self::reencrypt::<SignerState>(&backup_path, password_hash, &new_password_hash)
+                    .await?
+                {
+                    warn!(
+                        "removing {} state backup which cannot be decrypted.",
+                        network
+                    )?;
+                    Self::mark_removed(&backup_path).await?;
+                }
+                Self::reencrypt::<History>(
+                    &config.history_file(&network),
+                    password_hash,
+                    &new_password_hash,
+                )
+                .await?;
+            }
+            Self::reencrypt::<WalletMetadata>(
+                &config.wallet_path,
+                password_hash,
+                &new_password_hash,
+            )
+            .await?;
+            Self::reencrypt::<AddressBook>(
+                &config.address_book_path,
+                password_hash,
+                &new_password_hash,
+            )
+            .await?;
+            fs::rename(&pending_header, &config.kdf_header_path).await?;
+            Ok(())
+        }
+        .await;
+        Self::finish_rekey(config).await?;
+        result.map(|_| new_password_hash)
+    }
+
+    /// Decrypts the file at `path` with `password_hash` and writes it to its pending path
+    /// encrypted with `new_password_hash`, returning `false` if the file exists but cannot be
+    /// decrypted.
+    #[inline]
+    async fn reencrypt<T>(
+        path: &Path,
+        password_hash: &PasswordHash<Argon2>,
+        new_password_hash: &PasswordHash<Argon2>,
+    ) -> Result<bool>
+    where
+        T: DeserializeOwned + Serialize + Send + 'static,
+    {
+        if fs::metadata(path).await.is_err() {
+            return Ok(true);
+        }
+        let load_path = path.to_owned();
+        let password_hash_bytes = password_hash.as_bytes();
+        let data = match task::spawn_blocking(move || {
+            File::load::<_, T>(&load_path, &password_hash_bytes)
+        })
+        .await?
+        {
+            Ok(data) => data,
+            _ => {
+                warn!("unable to decrypt {}, leaving it as it is", path.display())?;
+                return Ok(false);
+            }
+        };
+        Self::save_encrypted(&kdf::pending_path(path), new_password_hash, data).await?;
+        Ok(true)
+    }
+
+    /// Completes a re-key or backup restore of the encrypted files of `config` which was
+    /// interrupted, or rolls it back if the new key derivation header was not moved into place
+    /// yet.
+    ///
+    /// Committing moves every pending file over the file it replaces, except for the empty files
+    /// written by [`mark_removed`], which remove the file they replace instead.
+    ///
+    /// [`mark_removed`]: Self::mark_removed
+    #[inline]
+    async fn finish_rekey(config: &Config) -> Result<()> {
+        let pending_header = kdf::pending_path(&config.kdf_header_path);
+        let committed = fs::metadata(&pending_header).await.is_err();
+        if !committed {
+            fs::remove_file(&pending_header).await?;
+        }
+        for path in config.encrypted_files() {
+            let pending = kdf::pending_path(&path);
+            let metadata = match fs::metadata(&pending).await {
+                Ok(metadata) => metadata,
+                _ => continue,
+            };
+            if !committed {
+                fs::remove_file(&pending).await?;
+            } else if metadata.len() == 0 {
+                if fs::metadata(&path).await.is_ok() {
+                    fs::remove_file(&path).await?;
+                }
+                fs::remove_file(&pending).await?;
+            } else {
+                fs::rename(&pending, &path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the initial signer state for a new account with the given `keys`.
     #[inline]
     async fn create_state(
         data_path: &Path,
         password_hash: &PasswordHash<Argon2>,
-        mnemonic: Mnemonic,
+        keys: TestnetKeySecret,
         parameters: &SignerParameters,
     ) -> Result<SignerState> {
         info!("creating signer state")?;
+        let state = Self::new_state(keys, parameters).await?;
+        info!("saving signer state")?;
+        let data_path = data_path.to_owned();
+        let password_hash_bytes = password_hash.as_bytes();
+        let cloned_state = state.clone();
+        task::spawn_blocking(move || File::save(&data_path, &password_hash_bytes, cloned_state))
+            .await??;
+        Ok(state)
+    }
+
+    /// Builds a signer state for `keys` with an empty UTXO accumulator and the default
+    /// authorization context.
+    #[inline]
+    async fn new_state(
+        keys: TestnetKeySecret,
+        parameters: &SignerParameters,
+    ) -> Result<SignerState> {
+        // NOTE: Recovered wallets also start from an empty accumulator and synchronize from
+        //       genesis. The accumulator needs every UTXO on the ledger to build membership
+        //       proofs, so the ledger history from before the wallet was created cannot be
+        //       skipped.
         let mut state = SignerState::new(UtxoAccumulator::new(
-            task::spawn_blocking(crate::parameters::load_utxo_accumulator_model)
-                .await?
-                .ok_or(Error::ParameterLoadingError)?,
+            task::spawn_blocking(crate::parameters::load_utxo_accumulator_model).await??,
         ));
-        let accounts = AccountTable::new(TestnetKeySecret::new(mnemonic, ""));
+        let accounts = AccountTable::new(keys);
         state.load_authorization_context(default_authorization_context::<MantaPayConfig>(
             &accounts,
             &parameters.parameters,
         ));
         state.load_accounts(accounts);
-
-        info!("saving signer state")?;
-        let data_path = data_path.to_owned();
-        let password_hash_bytes = password_hash.as_bytes();
-        let cloned_state = state.clone();
-        task::spawn_blocking(move || File::save(&data_path, &password_hash_bytes, cloned_state))
-            .await??;
         Ok(state)
     }
 
@@ -598,12 +1701,18 @@ where
         {
             let mnemonic = state.accounts().keys().expose_mnemonic().clone();
 
-            let encoded: Vec<u8> = bincode::serialize(&mnemonic).expect("encoding mnenomic failed");
+            let encoded: Vec<u8> =
+                bincode::serialize(&mnemonic).map_err(|_| Error::StateLoadingError)?;
             let new_mnemonic: Mnemonic =
-                bincode::deserialize(&encoded[..]).expect("decoding mnenomic failed");
+                bincode::deserialize(&encoded[..]).map_err(|_| Error::StateLoadingError)?;
 
-            let new_state =
-                Self::create_state(data_path, password_hash, new_mnemonic, parameters).await?;
+            let new_state = Self::create_state(
+                data_path,
+                password_hash,
+                TestnetKeySecret::new(new_mnemonic, ""),
+                parameters,
+            )
+            .await?;
 
             Ok(Some(new_state))
         } else {
@@ -611,18 +1720,114 @@ where
         }
     }
 
+    /// Loads the encrypted auxiliary data, like the transaction history or the address book, from
+    /// `path`, starting over from the default value if there is none.
+    ///
+    /// A file which cannot be decrypted, for example because it was encrypted under a different
+    /// password, is moved aside with [`unreadable_path`] before starting over, so that it is never
+    /// overwritten.
+    #[inline]
+    async fn load_encrypted<T>(path: &Path, password_hash: &PasswordHash<Argon2>) -> Result<T>
+    where
+        T: Default + DeserializeOwned + Send + 'static,
+    {
+        if fs::metadata(path).await.is_err() {
+            return Ok(T::default());
+        }
+        let owned_path = path.to_owned();
+        let password_hash_bytes = password_hash.as_bytes();
+        match task::spawn_blocking(move || File::load::<_, T>(&owned_path, &password_hash_bytes))
+            .await?
+        {
+            Ok(data) => Ok(data),
+            _ => {
+                let unreadable = unreadable_path(path);
+                fs::rename(path, &unreadable).await?;
+                warn!(
+                    "unable to decrypt {}, moved it to {} and starting over",
+                    path.display(),
+                    unreadable.display()
+                )?;
+                Ok(T::default())
+            }
+        }
+    }
+
+    /// Saves the auxiliary `data` to `path`, encrypted under the password key.
+    #[inline]
+    async fn save_encrypted<T>(
+        path: &Path,
+        password_hash: &PasswordHash<Argon2>,
+        data: T,
+    ) -> Result<()>
+    where
+        T: Serialize + Send + 'static,
+    {
+        let path = path.to_owned();
+        let password_hash_bytes = password_hash.as_bytes();
+        task::spawn_blocking(move || File::save(path, &password_hash_bytes, data)).await??;
+        Ok(())
+    }
+
+    /// Appends `entry` to the transaction history for `network` and saves it to disk in the
+    /// background.
+    #[inline]
+    async fn record(&self, network: &NetworkId, entry: Entry) {
+        self.state.lock().history(network).push(entry);
+        let network = network.clone();
+        let server = self.clone();
+        task::spawn(async move {
+            if server.save_history(network).await.is_err() {
+                let _ = warn!("unable to save transaction history");
+            }
+        });
+    }
+
+    /// Saves the transaction history corresponding to `network` to disk.
+    #[inline]
+    async fn save_history(self, network: NetworkId) -> Result<()> {
+        let path = self.state.lock().config.history_file(&network);
+        let password_hash_bytes = self.authorizer.lock().await.password_hash.as_bytes();
+        task::spawn_blocking(move || {
+            let lock = self.state.lock();
+            match lock.history.get(&network) {
+                Some(history) => File::save(path, &password_hash_bytes, history),
+                _ => Ok(()),
+            }
+        })
+        .await??;
+        Ok(())
+    }
+
+    /// Saves the address book to disk.
+    #[inline]
+    async fn save_address_book(&self) -> Result<()> {
+        let path = self.state.lock().config.address_book_path.clone();
+        let password_hash_bytes = self.authorizer.lock().await.password_hash.as_bytes();
+        let state = self.state.clone();
+        task::spawn_blocking(move || {
+            let lock = state.lock();
+            File::save(path, &password_hash_bytes, &lock.address_book)
+        })
+        .await??;
+        Ok(())
+    }
+
     /// Saves the signer state corresponding to `network` to disk.
     #[inline]
-    async fn save(self, network: Network) -> Result<()> {
+    async fn save(self, network: NetworkId) -> Result<()> {
         info!("starting signer state save to disk for {}", network)?;
-        let path = self.state.lock().config.data_path[network].clone();
-        let backup = self.state.lock().config.backup_data_path[network].clone();
+        let path = self.state.lock().config.data_file(&network);
+        let backup = self.state.lock().config.backup_data_file(&network);
         fs::rename(&path, &backup).await?;
         let password_hash_bytes = self.authorizer.lock().await.password_hash.as_bytes();
+        let saved_network = network.clone();
         task::spawn_blocking(move || {
             let lock = self.state.lock();
-            let state_to_save = lock.signer[network].state();
-            File::save(path, &password_hash_bytes, state_to_save)
+            match lock.signer.get(&saved_network) {
+                Some(signer) => File::save(path, &password_hash_bytes, signer.state()),
+                _ => Ok(()),
+            }
         })
         .await??;
         fs::remove_file(backup).await?;
@@ -641,7 +1846,13 @@ where
     #[inline]
     pub async fn sync(self, request: SyncRequest) -> Result<Result<SyncResponse, SyncError>> {
         info!("[REQUEST] processing `sync`:  {:?}.", request)?;
-        let response = self.state.lock().signer[request.network].sync(request.message);
+        Self::check_signing_account(request.account)?;
+        self.load_signer(&request.network).await?;
+        let response = self
+            .state
+            .lock()
+            .signer(&request.network)?
+            .sync(request.message);
         task::spawn(async move {
             if self.save(request.network).await.is_err() {
                 let _ = warn!("unable to save current signer state");
@@ -651,14 +1862,68 @@ where
         Ok(response)
     }
 
-    /// Runs the transaction signing protocol on the signer.
+    /// Discards the synchronization state of the signer on `request.network`, replacing it with a
+    /// fresh state built from the same accounts so that the next `sync` starts from genesis.
+    ///
+    /// The password, mnemonic and transaction history are left untouched. The user has to approve
+    /// the reset with their password, whether it is requested by a dApp or from the UI.
     #[inline]
-    pub async fn sign(self, request: SignRequest) -> Result<Result<SignResponse, SignError>> {
-        info!("[REQUEST] processing `sign`: {:?}.", request)?;
+    pub async fn reset_sync_state(self, request: ResetSyncStateRequest) -> Result<()> {
+        let network = request.network;
+        info!("[REQUEST] processing `reset_sync_state` for {}.", network)?;
+        self.load_signer(&network).await?;
         if self.state.lock().currently_signing {
             return Err(Error::Delayed);
         }
-        self.state.lock().currently_signing = true;
+        info!("[AUTH] asking for sync state reset authorization")?;
+        self.authorizer
+            .lock()
+            .await
+            .check(&ActionRequest::reset_sync_state(network.clone()))
+            .await?;
+        let keys = self
+            .state
+            .lock()
+            .signer(&network)?
+            .state()
+            .accounts()
+            .as_ref()
+            .ok_or(Error::MissingAccounts(network.clone()))?
+            .keys()
+            .clone();
+        let parameters = self.network_parameters(&network).await?;
+        let signer_state = Self::new_state(keys, &parameters).await?;
+        {
+            let mut state = self.state.lock();
+            if state.currently_signing {
+                return Err(Error::Delayed);
+            }
+            state
+                .signer
+                .insert(network.clone(), Self::new_signer(parameters, signer_state));
+        }
+        info!("reset synchronization state for {}.", network)?;
+        self.save(network).await
+    }
+
+    /// Runs the transaction signing protocol on the signer.
+    #[inline]
+    pub async fn sign(self, request: SignRequest) -> Result<Result<SignResponse, SignError>> {
+        self.sign_with_origin(request, None).await
+    }
+
+    /// Runs the transaction signing protocol on the signer for a request sent from `origin`,
+    /// recording the outcome in the transaction history.
+    #[inline]
+    pub async fn sign_with_origin(
+        self,
+        request: SignRequest,
+        origin: Option<String>,
+    ) -> Result<Result<SignResponse, SignError>> {
+        info!("[REQUEST] processing `sign`: {:?}.", request)?;
+        Self::check_signing_account(request.account)?;
+        self.load_signer(&request.network).await?;
+        let _signing = SigningGuard::start(&self.state)?;
         let SignRequest {
             network,
             message:
@@ -666,7 +1931,9 @@ where
                     transaction,
                     metadata,
                 },
+            ..
         } = request;
+        let mut entry = Entry::new(&transaction, origin.clone(), Outcome::Signed);
         match transaction.shape() {
             TransferShape::ToPrivate => {
                 // NOTE: We skip authorization on mint transactions because they are deposits not
@@ -674,56 +1941,498 @@ where
                 //       default, requests authorization.
             }
             _ => {
+                if metadata.is_none()
+                    && self.state.lock().config.missing_metadata_policy == AssetPolicy::Reject
+                {
+                    warn!("[AUTH] rejecting transaction without asset metadata")?;
+                    entry.outcome = Outcome::Rejected;
+                    self.record(&network, entry).await;
+                    return Err(Error::MissingAssetMetadata);
+                }
+                let asset_id = asset::display_id(&entry.asset_id);
+                let warning = metadata.as_ref().and_then(|metadata| {
+                    match self.state.lock().asset_registry.check(
+                        &network,
+                        &entry.asset_id,
+                        metadata,
+                    ) {
+                        Check::Match => None,
+                        Check::Unregistered => Some(AuthorizationWarning::UnregisteredAsset {
+                            asset_id: asset_id.clone(),
+                            symbol: metadata.symbol.clone(),
+                        }),
+                        Check::Mismatch(registered) => {
+                            Some(AuthorizationWarning::MetadataMismatch {
+                                asset_id: asset_id.clone(),
+                                registered,
+                            })
+                        }
+                        Check::Collision(registered) => {
+                            Some(AuthorizationWarning::SymbolCollision {
+                                asset_id: asset_id.clone(),
+                                registered,
+                            })
+                        }
+                    }
+                });
+                // NOTE: Claiming the symbol of a registered asset is treated like a mismatch, since
+                //       it is how a malicious site would disguise one asset as another.
+                if matches!(
+                    warning,
+                    Some(
+                        AuthorizationWarning::MetadataMismatch { .. }
+                            | AuthorizationWarning::SymbolCollision { .. }
+                    )
+                ) && self.state.lock().config.metadata_mismatch_policy == AssetPolicy::Reject
+                {
+                    warn!("[AUTH] rejecting transaction with mismatched asset metadata")?;
+                    entry.outcome = Outcome::Rejected;
+                    self.record(&network, entry).await;
+                    return Err(Error::AssetMetadataMismatch);
+                }
                 info!("[AUTH] asking for transaction authorization")?;
-                let summary = metadata
-                    .map(|metadata| display_transaction(&transaction, &metadata, network))
-                    .unwrap_or_default();
-                self.authorizer.lock().await.check(&summary).await?
+                let mut prompt =
+                    self.authorization_request(&network, &transaction, metadata.as_ref(), origin);
+                if let Some(warning) = warning {
+                    prompt = prompt.with_warning(warning);
+                }
+                let authorization = self.authorizer.lock().await.check(&prompt).await;
+                if let Err(err) = authorization {
+                    entry.outcome = Outcome::Declined;
+                    self.record(&network, entry).await;
+                    return Err(err);
+                }
             }
         }
-        let response = self.state.lock().signer[network].sign(transaction);
+        let response = self.state.lock().signer(&network)?.sign(transaction);
         info!("[RESPONSE] responding to `sign` with: {:?}.", response)?;
-        self.state.lock().currently_signing = false;
+        if response.is_err() {
+            entry.outcome = Outcome::Failed;
+        }
+        self.record(&network, entry).await;
         Ok(response)
     }
 
+    /// Builds the [`AuthorizationRequest`] for `transaction` on `network`, assigning it the next
+    /// request id.
+    #[inline]
+    fn authorization_request(
+        &self,
+        network: &NetworkId,
+        transaction: &Transaction,
+        metadata: Option<&AssetMetadata>,
+        origin: Option<String>,
+    ) -> AuthorizationRequest {
+        let mut state = self.state.lock();
+        let request_id = state.next_request_id;
+        state.next_request_id += 1;
+        let is_new_recipient = match transaction {
+            Transaction::PrivateTransfer(_, address) => !state
+                .history(network)
+                .has_signed_for(&address_to_base58(address)),
+            _ => false,
+        };
+        AuthorizationRequest::new(
+            request_id,
+            network.clone(),
+            transaction,
+            metadata,
+            &state.address_book,
+            is_new_recipient,
+            origin,
+        )
+    }
+
+    /// Returns the page of the transaction history for `request.network` selected by
+    /// `request.message`, newest entries first.
+    #[inline]
+    pub async fn history(self, request: HistoryRequest) -> Result<HistoryPage> {
+        info!("[REQUEST] processing `history` for {}.", request.network)?;
+        self.check_enabled(&request.network)?;
+        self.authorize_read(format!(
+            "View transaction history on {} network",
+            request.network
+        ))
+        .await?;
+        Ok(self
+            .state
+            .lock()
+            .history(&request.network)
+            .page(request.message))
+    }
+
+    /// Exports the full transaction history for `request.network` in the format given by
+    /// `request.message`.
+    #[inline]
+    pub async fn export_history(self, request: HistoryExportRequest) -> Result<String> {
+        info!(
+            "[REQUEST] processing `export_history` for {}.",
+            request.network
+        )?;
+        self.check_enabled(&request.network)?;
+        self.authorize_read(format!(
+            "Export transaction history on {} network",
+            request.network
+        ))
+        .await?;
+        Ok(self
+            .state
+            .lock()
+            .history(&request.network)
+            .export(request.message))
+    }
+
+    /// Asks the user to authorize a read-only query with `prompt` if
+    /// [`Config::require_read_authorization`] is set.
+    #[inline]
+    async fn authorize_read(&self, prompt: String) -> Result<()> {
+        if self.state.lock().config.require_read_authorization {
+            info!("[AUTH] asking for read authorization")?;
+            self.authorizer.lock().await.check(&prompt).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns all the entries of the address book.
+    #[inline]
+    pub fn address_book(&self) -> Vec<AddressBookEntry> {
+        self.state.lock().address_book.entries()
+    }
+
+    /// Labels the base58-encoded `address` with `label` in the address book and saves it to disk.
+    ///
+    /// The address book can only be edited locally, so this method is not exposed over HTTP.
+    #[inline]
+    pub async fn set_address_label(&self, address: String, label: String) -> Result<()> {
+        let address = address_book::canonical_address(&address).ok_or(Error::InvalidAddress)?;
+        self.state.lock().address_book.insert(address, label);
+        self.save_address_book().await
+    }
+
+    /// Removes the label of the base58-encoded `address` from the address book and saves it to
+    /// disk, returning `true` if there was a label to remove.
+    #[inline]
+    pub async fn remove_address_label(&self, address: &str) -> Result<bool> {
+        let removed = self.state.lock().address_book.remove(address).is_some();
+        if removed {
+            self.save_address_book().await?;
+        }
+        Ok(removed)
+    }
+
+    /// Returns all the assets registered in the signer for `network`, bundled assets first.
+    #[inline]
+    pub fn registered_assets(&self, network: &NetworkId) -> Vec<RegisteredAsset> {
+        self.state.lock().asset_registry.assets(network)
+    }
+
+    /// Adds the custom `asset` to the signer asset registry for `network` and saves it to disk,
+    /// returning `false` if it conflicts with a bundled asset.
+    #[inline]
+    pub async fn add_custom_asset(
+        &self,
+        network: &NetworkId,
+        asset: RegisteredAsset,
+    ) -> Result<bool> {
+        let (registry, path) = {
+            let mut state = self.state.lock();
+            if !state.asset_registry.insert(network, asset) {
+                return Ok(false);
+            }
+            (
+                state.asset_registry.clone(),
+                state.config.asset_registry_path.clone(),
+            )
+        };
+        registry.save(path).await?;
+        Ok(true)
+    }
+
+    /// Removes the custom asset with the given `id` from the signer asset registry for `network`
+    /// and saves it to disk, returning `true` if there was an asset to remove.
+    #[inline]
+    pub async fn remove_custom_asset(&self, network: &NetworkId, id: u128) -> Result<bool> {
+        let (registry, path) = {
+            let mut state = self.state.lock();
+            if state.asset_registry.remove(network, id).is_none() {
+                return Ok(false);
+            }
+            (
+                state.asset_registry.clone(),
+                state.config.asset_registry_path.clone(),
+            )
+        };
+        registry.save(path).await?;
+        Ok(true)
+    }
+
     /// Gets the mnemonic stored on disk for a specific `network` for front-end export
     /// requiring password authorization.
     #[inline]
     pub async fn get_stored_mnemonic(
         &mut self,
-        network: Network,
+        network: &NetworkId,
         prompt: &String,
     ) -> Result<Mnemonic> {
+        self.load_signer(network).await?;
         self.authorizer.lock().await.check(prompt).await?;
-        let stored_mnemonic = self.state.lock().signer[network]
+        let stored_mnemonic = self
+            .state
+            .lock()
+            .signer(network)?
             .state()
             .accounts()
             .as_ref()
-            .expect("No accounts in signer")
+            .ok_or_else(|| Error::MissingAccounts(network.clone()))?
             .keys()
             .expose_mnemonic()
             .clone();
         Ok(stored_mnemonic)
     }
 
+    /// Writes a portable backup of the wallet to `path`, encrypted under `backup_password`, after
+    /// asking the user to authorize it with `prompt`.
+    ///
+    /// The backup holds the signer state of every enabled network so that it can be restored
+    /// without resynchronizing. It can only be exported locally, so this method is not exposed
+    /// over HTTP.
+    #[inline]
+    pub async fn export_backup(
+        &self,
+        path: &Path,
+        prompt: &String,
+        backup_password: SecretString,
+    ) -> Result<()> {
+        info!("[REQUEST] processing `export_backup`.")?;
+        let networks = self.state.lock().config.enabled();
+        for network in &networks {
+            self.load_signer(network).await?;
+        }
+        self.authorizer.lock().await.check(prompt).await?;
+        let backup = {
+            let mut state = self.state.lock();
+            let mnemonic = state
+                .key_secret()
+                .ok_or(Error::StateLoadingError)?
+                .expose_mnemonic()
+                .clone();
+            let states = networks
+                .iter()
+                .map(|network| Ok((network.name(), state.signer(network)?.state().clone())))
+                .collect::<Result<Vec<_>>>()?;
+            Backup {
+                mnemonic,
+                wallet: state.wallet.clone(),
+                address_book: state.address_book.clone(),
+                states,
+            }
+        };
+        let file = task::spawn_blocking(move || {
+            backup.encrypt(backup_password.expose_secret().as_bytes())
+        })
+        .await??;
+        fs::write(path, file).await?;
+        info!("[RESPONSE] exported backup to {:?}.", path)?;
+        Ok(())
+    }
+
+    /// Returns the stored mnemonic split into `count` shares, any `threshold` of which recover it,
+    /// after asking the user to authorize it with `prompt`.
+    ///
+    /// See [`shares`] for the format of the shares.
+    #[inline]
+    pub async fn get_stored_shares(
+        &mut self,
+        network: &NetworkId,
+        prompt: &String,
+        threshold: u8,
+        count: u8,
+    ) -> Result<Vec<String>> {
+        shares::check_parameters(threshold, count)?;
+        let mnemonic = self.get_stored_mnemonic(network, prompt).await?;
+        Ok(shares::split_mnemonic(&mnemonic, threshold, count)?)
+    }
+
     /// Runs the receiving key sampling protocol on the signer.
+    ///
+    /// Only the default account is synchronized and signed for, so requests which do not name an
+    /// account get its address and requests for any other account are rejected, to keep dApps from
+    /// sending funds to an address the signer does not track.
     #[inline]
     pub async fn address(self, request: ReceivingKeyRequest) -> Result<Address> {
-        let response = self.state.lock().signer[request.network]
-            .address()
-            .expect("No address present in signer!");
+        Self::check_signing_account(request.account)?;
+        self.load_signer(&request.network).await?;
+        let response = {
+            let mut state = self.state.lock();
+            Self::account_address(&mut state, &request.network, DEFAULT_ACCOUNT)?
+        };
         info!("[RESPONSE] responding to `receivingKeys` with: {response:?}")?;
         Ok(response)
     }
 
+    /// Returns an error if `account` names an account other than the default account, since only
+    /// the default account is backed by a signer state.
+    #[inline]
+    fn check_signing_account(account: Option<u32>) -> Result<()> {
+        match account {
+            Some(index) if index != DEFAULT_ACCOUNT => Err(Error::UnsupportedAccount),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the address of the account with the given `index` on `network`.
+    ///
+    /// Only the default account is synchronized and signed for, so the addresses of the other
+    /// accounts are not handed out and [`Error::UnsupportedAccount`] is returned for them.
+    #[inline]
+    fn account_address(state: &mut State, network: &NetworkId, index: u32) -> Result<Address> {
+        if !state.wallet.has_account(index) {
+            return Err(Error::UnknownAccount);
+        }
+        if index != DEFAULT_ACCOUNT {
+            return Err(Error::UnsupportedAccount);
+        }
+        state
+            .signer(network)?
+            .address()
+            .ok_or_else(|| Error::MissingAccounts(network.clone()))
+    }
+
+    /// Returns the first enabled network, which is used for local queries that do not name a
+    /// network. [`Server::build`] makes sure that at least one network is enabled.
+    #[inline]
+    pub fn default_network(&self) -> NetworkId {
+        self.state.lock().config.enabled()[0].clone()
+    }
+
+    /// Returns the name of the profile the server was built for.
+    #[inline]
+    pub fn profile(&self) -> String {
+        self.state.lock().config.profile.clone()
+    }
+
+    /// Returns the number of failed password attempts since the last successful login, which the
+    /// user should be told about.
+    #[inline]
+    pub fn failed_password_attempts(&self) -> u32 {
+        self.state.lock().failed_password_attempts
+    }
+
+    /// Returns the names of all the profiles of the signer installation.
+    #[inline]
+    pub async fn profiles(&self) -> Result<Vec<String>> {
+        let config = self.state.lock().config.clone();
+        Ok(config.profiles().await?)
+    }
+
+    /// Returns the accounts of the wallet with their labels, and the address of the default
+    /// account.
+    #[inline]
+    pub fn accounts(&self) -> Result<Vec<AccountInfo>> {
+        let mut state = self.state.lock();
+        let network = state.loaded_network()?;
+        let address = address_to_base58(&Self::account_address(
+            &mut state,
+            &network,
+            DEFAULT_ACCOUNT,
+        )?);
+        Ok((0..state.wallet.account_count())
+            .map(|index| AccountInfo {
+                index,
+                label: state.wallet.label(index).map(ToOwned::to_owned),
+                address: (index == DEFAULT_ACCOUNT).then(|| address.clone()),
+                selected: state.wallet.selected_account == index,
+            })
+            .collect())
+    }
+
+    /// Creates a new account in the wallet with the optional `label`, returning its index.
+    ///
+    /// The new account is not synchronized or signed for yet, so it has no address.
+    #[inline]
+    pub async fn create_account(&self, label: Option<String>) -> Result<u32> {
+        let index = self.state.lock().wallet.create_account(label);
+        self.save_wallet().await?;
+        Ok(index)
+    }
+
+    /// Sets the `label` of the account with the given `index`.
+    #[inline]
+    pub async fn set_account_label(&self, index: u32, label: Option<String>) -> Result<()> {
+        if !self.state.lock().wallet.set_label(index, label) {
+            return Err(Error::UnknownAccount);
+        }
+        self.save_wallet().await
+    }
+
+    /// Selects the account with the given `index`, which the UI shows as the current account.
+    #[inline]
+    pub async fn select_account(&self, index: u32) -> Result<()> {
+        if !self.state.lock().wallet.select(index) {
+            return Err(Error::UnknownAccount);
+        }
+        self.save_wallet().await
+    }
+
+    /// Saves the wallet metadata to disk.
+    #[inline]
+    async fn save_wallet(&self) -> Result<()> {
+        let (path, wallet) = {
+            let state = self.state.lock();
+            (state.config.wallet_path.clone(), state.wallet.clone())
+        };
+        let password_hash = self.authorizer.lock().await.password_hash.clone();
+        Self::save_encrypted(&path, &password_hash, wallet).await
+    }
+
+    /// Returns the private balances held by the signer on `request.network`, summing over all the
+    /// UTXOs in the signer asset map for each asset id.
+    ///
+    /// # Authorization
+    ///
+    /// This method does not modify the signer state. If [`Config::require_read_authorization`] is
+    /// set, the user is prompted for their password before the balances are returned.
+    #[inline]
+    pub async fn balances(self, request: BalanceRequest) -> Result<BalanceResponse> {
+        info!("[REQUEST] processing `balances` for {}.", request.network)?;
+        self.load_signer(&request.network).await?;
+        self.authorize_read(format!(
+            "View private balances on {} network",
+            request.network
+        ))
+        .await?;
+        let mut response = self
+            .state
+            .lock()
+            .signer(&request.network)?
+            .state()
+            .assets()
+            .iter()
+            .map(|(id, entries)| {
+                Asset::new(
+                    *id,
+                    entries.iter().map(|(_, value)| *value).sum::<AssetValue>(),
+                )
+            })
+            .filter(|asset| asset.value != 0)
+            .collect::<BalanceResponse>();
+        response.sort_by(|lhs, rhs| lhs.id.cmp(&rhs.id));
+        info!("[RESPONSE] responding to `balances` with: {response:?}")?;
+        Ok(response)
+    }
+
     /// Runs the receiving key sampling protocol on a mutable reference of the signer, and formats
     /// the result to base 58.
+    ///
+    /// Requests which do not name an account get the address of the default account, which is
+    /// the only account that is synchronized. Requests for any other account fail.
     #[inline]
     pub async fn get_address(&mut self, request: ReceivingKeyRequest) -> Result<String, ()> {
-        let response = self.state.lock().signer[request.network]
-            .address()
-            .ok_or(())?;
+        self.load_signer(&request.network).await.map_err(|_| ())?;
+        let mut state = self.state.lock();
+        let index = request.account.unwrap_or(DEFAULT_ACCOUNT);
+        let response =
+            Self::account_address(&mut state, &request.network, index).map_err(|_| ())?;
         let key = address_to_base58(&response);
         Ok(key)
     }
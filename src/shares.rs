@@ -0,0 +1,516 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Recovery Phrase Shares
+//!
+//! The entropy of the recovery phrase can be split into `count` shares such that any `threshold`
+//! of them recover it, while fewer reveal nothing about it. The split uses Shamir's secret
+//! sharing over GF(2^8) with the reduction polynomial `x^8 + x^4 + x^3 + x + 1`, byte by byte.
+//!
+//! # Share Format
+//!
+//! Every share is written as `mss1-<id>-<threshold>-<index>-<data>` where:
+//!
+//! - `id` is four lowercase hex digits picked at random for each split, so that shares of
+//!   different splits are not combined by mistake,
+//! - `threshold` is the number of shares needed to recover the phrase,
+//! - `index` is the x-coordinate of the share, between `1` and the number of shares, and
+//! - `data` is the lowercase hex encoding of the share of the phrase entropy followed by the
+//!   first four bytes of its SHA-256 digest, which is checked after combining.
+//!
+//! Shares hold the phrase entropy and not its words, and are always turned back into an English
+//! phrase, which is the only language wallets are created from. See the
+//! [`mnemonic`](crate::mnemonic) module for the supported languages.
+
+use bip0039::English;
+use core::fmt::{self, Display};
+use manta_crypto::rand::{OsRng, RngCore};
+use manta_pay::key::Mnemonic;
+use sha2::{Digest, Sha256};
+use std::error;
+
+/// Share Prefix
+///
+/// Versions the share format.
+pub const PREFIX: &str = "mss1";
+
+/// Maximum Number of Shares
+pub const MAX_SHARES: u8 = 16;
+
+/// Checksum Length
+const CHECKSUM_LENGTH: usize = 4;
+
+/// Share Error
+#[derive(Debug, Eq, PartialEq)]
+pub enum ShareError {
+    /// Invalid Parameters Error
+    ///
+    /// The threshold must be at least two and at most the number of shares, which is at most
+    /// [`MAX_SHARES`].
+    InvalidParameters,
+
+    /// Malformed Share Error
+    ///
+    /// The share at the given position is not written in the share format.
+    Malformed(usize),
+
+    /// Mismatched Shares Error
+    ///
+    /// The shares come from different splits.
+    Mismatched,
+
+    /// Duplicate Share Error
+    ///
+    /// Two of the shares have the same index.
+    Duplicate,
+
+    /// Not Enough Shares Error
+    ///
+    /// Fewer shares than the threshold were given.
+    NotEnough {
+        /// Number of Shares Needed
+        threshold: u8,
+
+        /// Number of Shares Given
+        given: usize,
+    },
+
+    /// Checksum Error
+    ///
+    /// The shares combine to a secret which does not match its checksum, so at least one of them
+    /// was mistyped.
+    Checksum,
+
+    /// Invalid Mnemonic Error
+    ///
+    /// The shares combine to a secret which is not the entropy of a recovery phrase.
+    InvalidMnemonic,
+}
+
+impl Display for ShareError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidParameters => write!(
+                f,
+                "the threshold must be between 2 and the number of shares, which is at most {MAX_SHARES}"
+            ),
+            Self::Malformed(position) => write!(f, "share {} is malformed", position + 1),
+            Self::Mismatched => write!(f, "the shares belong to different splits"),
+            Self::Duplicate => write!(f, "the same share was given twice"),
+            Self::NotEnough { threshold, given } => {
+                write!(f, "{threshold} shares are needed but only {given} were given")
+            }
+            Self::Checksum => write!(f, "the shares do not match their checksum"),
+            Self::InvalidMnemonic => write!(f, "the shares do not hold a recovery phrase"),
+        }
+    }
+}
+
+impl error::Error for ShareError {}
+
+/// Multiplies `lhs` and `rhs` in GF(2^8).
+#[inline]
+fn mul(mut lhs: u8, mut rhs: u8) -> u8 {
+    let mut product = 0;
+    while rhs != 0 {
+        if rhs & 1 != 0 {
+            product ^= lhs;
+        }
+        let carry = lhs & 0x80 != 0;
+        lhs <<= 1;
+        if carry {
+            lhs ^= 0x1b;
+        }
+        rhs >>= 1;
+    }
+    product
+}
+
+/// Returns the multiplicative inverse of the non-zero `value` in GF(2^8).
+#[inline]
+fn inv(value: u8) -> u8 {
+    // NOTE: The multiplicative group has order 255, so `value^254` is the inverse of `value`.
+    let mut result = 1;
+    let mut base = value;
+    let mut exponent = 254u8;
+    while exponent != 0 {
+        if exponent & 1 != 0 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Encodes `bytes` as lowercase hex.
+#[inline]
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Decodes the lowercase or uppercase hex in `hex`.
+#[inline]
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Returns the checksum of `secret`.
+#[inline]
+fn checksum(secret: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let mut checksum = [0; CHECKSUM_LENGTH];
+    checksum.copy_from_slice(&Sha256::digest(secret)[..CHECKSUM_LENGTH]);
+    checksum
+}
+
+/// Share of a Secret
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Share {
+    /// Split Identifier
+    id: u16,
+
+    /// Threshold
+    threshold: u8,
+
+    /// Index
+    index: u8,
+
+    /// Share Data
+    data: Vec<u8>,
+}
+
+impl Share {
+    /// Parses a share written in the share format.
+    #[inline]
+    fn parse(share: &str) -> Option<Self> {
+        let mut parts = share.trim().split('-');
+        if parts.next()? != PREFIX {
+            return None;
+        }
+        let id = parts.next()?;
+        if id.len() != 4 {
+            return None;
+        }
+        let share = Self {
+            id: u16::from_str_radix(id, 16).ok()?,
+            threshold: parts.next()?.parse().ok()?,
+            index: parts.next()?.parse().ok()?,
+            data: from_hex(parts.next()?)?,
+        };
+        if parts.next().is_some()
+            || share.threshold < 2
+            || share.index == 0
+            || share.data.len() <= CHECKSUM_LENGTH
+        {
+            return None;
+        }
+        Some(share)
+    }
+}
+
+impl Display for Share {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{PREFIX}-{:04x}-{}-{}-{}",
+            self.id,
+            self.threshold,
+            self.index,
+            to_hex(&self.data)
+        )
+    }
+}
+
+/// Checks that a secret can be split into `count` shares, any `threshold` of which recover it.
+#[inline]
+pub fn check_parameters(threshold: u8, count: u8) -> Result<(), ShareError> {
+    if threshold < 2 || threshold > count || count > MAX_SHARES {
+        return Err(ShareError::InvalidParameters);
+    }
+    Ok(())
+}
+
+/// Splits `secret` into `count` shares, any `threshold` of which recover it.
+#[inline]
+pub fn split(secret: &[u8], threshold: u8, count: u8) -> Result<Vec<String>, ShareError> {
+    check_parameters(threshold, count)?;
+    let mut data = secret.to_vec();
+    data.extend_from_slice(&checksum(secret));
+    let mut id = [0; 2];
+    OsRng.fill_bytes(&mut id);
+    let mut coefficients = vec![0; threshold as usize - 1];
+    let mut shares = (1..=count)
+        .map(|index| Share {
+            id: u16::from_le_bytes(id),
+            threshold,
+            index,
+            data: Vec::with_capacity(data.len()),
+        })
+        .collect::<Vec<_>>();
+    for byte in data {
+        OsRng.fill_bytes(&mut coefficients);
+        for share in &mut shares {
+            // NOTE: Evaluates the polynomial with constant term `byte` at `share.index` using
+            //       Horner's method.
+            let value = coefficients
+                .iter()
+                .rev()
+                .fold(0, |acc, coefficient| mul(acc, share.index) ^ coefficient);
+            share.data.push(mul(value, share.index) ^ byte);
+        }
+    }
+    coefficients.fill(0);
+    Ok(shares.iter().map(ToString::to_string).collect())
+}
+
+/// Combines `shares` into the secret they were split from.
+#[inline]
+pub fn combine<S>(shares: &[S]) -> Result<Vec<u8>, ShareError>
+where
+    S: AsRef<str>,
+{
+    let shares = shares
+        .iter()
+        .enumerate()
+        .map(|(position, share)| {
+            Share::parse(share.as_ref()).ok_or(ShareError::Malformed(position))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let first = shares.first().ok_or(ShareError::NotEnough {
+        threshold: 2,
+        given: 0,
+    })?;
+    if shares.iter().any(|share| {
+        share.id != first.id
+            || share.threshold != first.threshold
+            || share.data.len() != first.data.len()
+    }) {
+        return Err(ShareError::Mismatched);
+    }
+    if shares.len() < first.threshold as usize {
+        return Err(ShareError::NotEnough {
+            threshold: first.threshold,
+            given: shares.len(),
+        });
+    }
+    let shares = &shares[..first.threshold as usize];
+    for (i, share) in shares.iter().enumerate() {
+        if shares[..i].iter().any(|other| other.index == share.index) {
+            return Err(ShareError::Duplicate);
+        }
+    }
+    // NOTE: Computes the Lagrange basis polynomials at zero, which are the same for every byte.
+    let basis = shares
+        .iter()
+        .map(|share| {
+            shares
+                .iter()
+                .filter(|other| other.index != share.index)
+                .fold(1, |acc, other| {
+                    mul(acc, mul(other.index, inv(other.index ^ share.index)))
+                })
+        })
+        .collect::<Vec<_>>();
+    let mut data = (0..first.data.len())
+        .map(|i| {
+            shares
+                .iter()
+                .zip(&basis)
+                .fold(0, |acc, (share, basis)| acc ^ mul(share.data[i], *basis))
+        })
+        .collect::<Vec<_>>();
+    let secret_length = data.len() - CHECKSUM_LENGTH;
+    if data[secret_length..] != checksum(&data[..secret_length]) {
+        data.fill(0);
+        return Err(ShareError::Checksum);
+    }
+    data.truncate(secret_length);
+    Ok(data)
+}
+
+/// Splits the entropy of `mnemonic` into `count` shares, any `threshold` of which recover it.
+///
+/// The `mnemonic` is always English, since [`Mnemonic`] only holds English phrases.
+#[inline]
+pub fn split_mnemonic(
+    mnemonic: &Mnemonic,
+    threshold: u8,
+    count: u8,
+) -> Result<Vec<String>, ShareError> {
+    // NOTE: `Mnemonic` serializes to its phrase, which is also how the front end receives it.
+    let phrase = serde_json::to_value(mnemonic)
+        .ok()
+        .and_then(|phrase| phrase.as_str().map(ToOwned::to_owned))
+        .ok_or(ShareError::InvalidMnemonic)?;
+    let mnemonic = bip0039::Mnemonic::<English>::from_phrase(phrase)
+        .map_err(|_| ShareError::InvalidMnemonic)?;
+    split(mnemonic.entropy(), threshold, count)
+}
+
+/// Combines `shares` into the English recovery phrase they were split from.
+#[inline]
+pub fn combine_mnemonic<S>(shares: &[S]) -> Result<Mnemonic, ShareError>
+where
+    S: AsRef<str>,
+{
+    let entropy = combine(shares)?;
+    let mnemonic = bip0039::Mnemonic::<English>::from_entropy(entropy)
+        .map_err(|_| ShareError::InvalidMnemonic)?;
+    Mnemonic::new(mnemonic.phrase()).map_err(|_| ShareError::InvalidMnemonic)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Valid English Phrase
+    const PHRASE: &str =
+        "legal winner thank year wave sausage worth useful legal winner thank yellow";
+
+    /// Checks that every non-zero element of GF(2^8) times its inverse is one.
+    #[test]
+    fn inverts_field_elements() {
+        assert_eq!(mul(0x53, 0xca), 1);
+        for value in 1..=u8::MAX {
+            assert_eq!(mul(value, inv(value)), 1, "{value}");
+        }
+    }
+
+    /// Checks that hex encoding and decoding round-trip and that malformed hex is rejected.
+    #[test]
+    fn encodes_hex() {
+        let bytes = [0x00, 0x7f, 0xab, 0xff];
+        assert_eq!(to_hex(&bytes), "007fabff");
+        assert_eq!(from_hex("007fabff").as_deref(), Some(&bytes[..]));
+        assert_eq!(from_hex("007FABFF").as_deref(), Some(&bytes[..]));
+        assert_eq!(from_hex("007"), None);
+        assert_eq!(from_hex("0g"), None);
+    }
+
+    /// Checks that the threshold must be between two and the number of shares.
+    #[test]
+    fn checks_parameters() {
+        assert_eq!(check_parameters(2, 3), Ok(()));
+        assert_eq!(check_parameters(MAX_SHARES, MAX_SHARES), Ok(()));
+        for (threshold, count) in [(0, 3), (1, 3), (4, 3), (2, MAX_SHARES + 1)] {
+            assert_eq!(
+                check_parameters(threshold, count),
+                Err(ShareError::InvalidParameters),
+                "{threshold} of {count}"
+            );
+        }
+    }
+
+    /// Checks that every subset of shares of the threshold size recovers the secret.
+    #[test]
+    fn recovers_from_any_threshold_subset() {
+        let secret = b"manta-signer shares".to_vec();
+        let shares = split(&secret, 3, 5).expect("The parameters are valid.");
+        assert_eq!(shares.len(), 5);
+        for i in 0..5 {
+            for j in i + 1..5 {
+                for k in j + 1..5 {
+                    let subset = [&shares[k], &shares[i], &shares[j]];
+                    assert_eq!(combine(&subset), Ok(secret.clone()), "{i} {j} {k}");
+                }
+            }
+        }
+        assert_eq!(combine(&shares), Ok(secret));
+    }
+
+    /// Checks that too few, duplicate, mismatched, malformed and mistyped shares are rejected.
+    #[test]
+    fn rejects_invalid_shares() {
+        let shares = split(b"secret", 2, 3).expect("The parameters are valid.");
+        assert_eq!(
+            combine::<&str>(&[]),
+            Err(ShareError::NotEnough {
+                threshold: 2,
+                given: 0
+            })
+        );
+        assert_eq!(
+            combine(&shares[..1]),
+            Err(ShareError::NotEnough {
+                threshold: 2,
+                given: 1
+            })
+        );
+        assert_eq!(
+            combine(&[&shares[0], &shares[0]]),
+            Err(ShareError::Duplicate)
+        );
+        let mut other = Share::parse(&shares[1]).expect("The share is well-formed.");
+        other.id = other.id.wrapping_add(1);
+        assert_eq!(
+            combine(&[shares[0].clone(), other.to_string()]),
+            Err(ShareError::Mismatched)
+        );
+        assert_eq!(
+            combine(&[shares[0].as_str(), "mss1-zzzz-2-2-00"]),
+            Err(ShareError::Malformed(1))
+        );
+        let mut mistyped = Share::parse(&shares[1]).expect("The share is well-formed.");
+        mistyped.data[0] ^= 1;
+        assert_eq!(
+            combine(&[shares[0].clone(), mistyped.to_string()]),
+            Err(ShareError::Checksum)
+        );
+    }
+
+    /// Checks that shares are parsed back from their written form.
+    #[test]
+    fn parses_shares() {
+        let share = Share {
+            id: 0xbeef,
+            threshold: 2,
+            index: 3,
+            data: vec![0, 1, 2, 3, 4],
+        };
+        assert_eq!(share.to_string(), "mss1-beef-2-3-0001020304");
+        assert_eq!(Share::parse(" mss1-beef-2-3-0001020304\n"), Some(share));
+        for malformed in [
+            "mss2-beef-2-3-0001020304",
+            "mss1-beef-1-3-0001020304",
+            "mss1-beef-2-0-0001020304",
+            "mss1-beef-2-3-00010203",
+            "mss1-bee-2-3-0001020304",
+            "mss1-beef-2-3-0001020304-00",
+        ] {
+            assert_eq!(Share::parse(malformed), None, "{malformed}");
+        }
+    }
+
+    /// Checks that a recovery phrase is recovered from its shares.
+    #[test]
+    fn recovers_mnemonics() {
+        let mnemonic = Mnemonic::new(PHRASE).expect("The phrase is valid.");
+        let shares = split_mnemonic(&mnemonic, 2, 3).expect("The parameters are valid.");
+        let recovered = combine_mnemonic(&shares[1..]).expect("The shares are valid.");
+        assert_eq!(
+            serde_json::to_value(recovered).expect("Encoding the mnemonic cannot fail."),
+            serde_json::Value::from(PHRASE)
+        );
+    }
+}
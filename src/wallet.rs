@@ -0,0 +1,182 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-signer.
+//
+// manta-signer is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-signer is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-signer. If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Signer Wallet Metadata
+//!
+//! Wallet-wide information which is not part of the per-network signer states is kept in a
+//! separate file, encrypted under the same password key as the signer states.
+
+use crate::secret::{
+    deserialize_optional_secret, serialize_optional_secret, ExposeSecret, SecretString,
+};
+use manta_pay::key::{Mnemonic, TestnetKeySecret};
+use manta_util::serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Default Account Index
+///
+/// The signer states are built for this account, so it is the only account which can sign and
+/// synchronize.
+pub const DEFAULT_ACCOUNT: u32 = 0;
+
+/// Returns the fingerprint of the key secret `keys`.
+///
+/// Fingerprints identify the wallet a signer state belongs to without revealing its mnemonic, so
+/// they can be shown to the user. The fingerprint covers the seed derived from the mnemonic and
+/// the BIP39 passphrase, so the same mnemonic with different passphrases, which derives different
+/// keys, has different fingerprints.
+#[inline]
+pub fn fingerprint(keys: &TestnetKeySecret) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"manta-signer key secret fingerprint");
+    hasher.update(bincode::serialize(keys).expect("Encoding the key secret cannot fail."));
+    hasher.finalize()[..4]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Account Information
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct AccountInfo {
+    /// Account Index
+    pub index: u32,
+
+    /// Label
+    pub label: Option<String>,
+
+    /// Base58-Encoded Address
+    ///
+    /// Only the default account has an address. The other accounts are not synchronized or
+    /// signed for, so their addresses are withheld to keep funds from being sent where the
+    /// signer cannot see or spend them.
+    pub address: Option<String>,
+
+    /// Selected Flag
+    pub selected: bool,
+}
+
+/// Wallet Metadata
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct WalletMetadata {
+    /// BIP39 Passphrase
+    ///
+    /// Optional passphrase, sometimes called the "25th word", which is mixed into the seed
+    /// together with the mnemonic.
+    #[serde(
+        default,
+        deserialize_with = "deserialize_optional_secret",
+        serialize_with = "serialize_optional_secret"
+    )]
+    pub passphrase: Option<SecretString>,
+
+    /// Account Labels
+    ///
+    /// One entry per account created in the wallet, indexed by account index. Wallets always
+    /// contain the default account even if this list is empty.
+    #[serde(default)]
+    pub accounts: Vec<Option<String>>,
+
+    /// Selected Account Index
+    ///
+    /// The account the UI shows as the current account. Requests which do not name an account
+    /// still use [`DEFAULT_ACCOUNT`], since it is the only account backed by a signer state.
+    #[serde(default)]
+    pub selected_account: u32,
+}
+
+impl WalletMetadata {
+    /// Builds a new [`WalletMetadata`] for a wallet using `passphrase`.
+    #[inline]
+    pub fn new(passphrase: Option<SecretString>) -> Self {
+        Self {
+            passphrase,
+            accounts: vec![None],
+            selected_account: DEFAULT_ACCOUNT,
+        }
+    }
+
+    /// Returns the passphrase of the wallet, or the empty passphrase if there is none.
+    #[inline]
+    pub fn passphrase(&self) -> &str {
+        self.passphrase
+            .as_ref()
+            .map(|passphrase| passphrase.expose_secret().as_str())
+            .unwrap_or_default()
+    }
+
+    /// Derives the key secret for `mnemonic` using the passphrase of the wallet.
+    #[inline]
+    pub fn key_secret(&self, mnemonic: Mnemonic) -> TestnetKeySecret {
+        TestnetKeySecret::new(mnemonic, self.passphrase())
+    }
+
+    /// Returns the number of accounts in the wallet.
+    #[inline]
+    pub fn account_count(&self) -> u32 {
+        (self.accounts.len() as u32).max(1)
+    }
+
+    /// Returns `true` if the account with the given `index` exists in the wallet.
+    #[inline]
+    pub fn has_account(&self, index: u32) -> bool {
+        index < self.account_count()
+    }
+
+    /// Returns the label of the account with the given `index`.
+    #[inline]
+    pub fn label(&self, index: u32) -> Option<&str> {
+        self.accounts
+            .get(index as usize)
+            .and_then(|label| label.as_deref())
+    }
+
+    /// Creates a new account with `label`, returning its index.
+    #[inline]
+    pub fn create_account(&mut self, label: Option<String>) -> u32 {
+        if self.accounts.is_empty() {
+            self.accounts.push(None);
+        }
+        self.accounts.push(label);
+        self.accounts.len() as u32 - 1
+    }
+
+    /// Sets the label of the account with the given `index`, returning `false` if there is no such
+    /// account.
+    #[inline]
+    pub fn set_label(&mut self, index: u32, label: Option<String>) -> bool {
+        if !self.has_account(index) {
+            return false;
+        }
+        if self.accounts.is_empty() {
+            self.accounts.push(None);
+        }
+        self.accounts[index as usize] = label;
+        true
+    }
+
+    /// Selects the account with the given `index`, returning `false` if there is no such account.
+    #[inline]
+    pub fn select(&mut self, index: u32) -> bool {
+        if !self.has_account(index) {
+            return false;
+        }
+        self.selected_account = index;
+        true
+    }
+}
@@ -31,23 +31,27 @@ use core::{
     time::Duration,
 };
 use manta_signer::{
+    address_book::AddressBookEntry,
+    attempts::PasswordDelay,
     config::{Config, Setup},
-    manta_pay::{
-        key::Mnemonic,
-        signer::{
-            client::network::{Message, Network},
-            GetRequest,
-        },
-    },
+    history::{ExportFormat, HistoryPage, HistoryQuery},
+    manta_pay::{key::Mnemonic, signer::GetRequest},
+    mnemonic::{self, MnemonicError, Wordlist},
+    network::NetworkId,
+    registry::RegisteredAsset,
     secret::{
-        mnemonic_channel, password_channel, sample_mnemonic, Authorizer, MnemonicReceiver,
-        MnemonicSender, Password, PasswordFuture, PasswordReceiver, PasswordSender, Secret,
-        SetupFuture, UnitFuture, UserSelection,
+        mnemonic_channel, password_channel, sample_mnemonic, Authorizer, DivergenceChoice,
+        DivergenceFuture, MnemonicReceiver, MnemonicSender, Password, PasswordFuture,
+        PasswordReceiver, PasswordSender, ProfileFuture, RecoveryChoice, RecoveryFuture,
+        RecoveryPhrase, Secret, SecretString, SetupFailure, SetupFuture, StateDivergence,
+        UnitFuture, UserSelection,
     },
     serde::Serialize,
-    service::Server,
+    service::{AccountMessage, BalanceResponse, Error, NetworkMessage, Server},
+    shares,
     storage::Store,
     tokio::fs,
+    wallet::AccountInfo,
 };
 use std::time::Instant;
 use tauri::{
@@ -153,6 +157,11 @@ pub struct User {
 
     /// Waiting Flag
     waiting: bool,
+
+    /// Requested Profile
+    ///
+    /// Profile the user switched to from the UI, selected once the server is built.
+    profile: Option<String>,
 }
 
 impl User {
@@ -168,9 +177,17 @@ impl User {
             password_receiver,
             mnemonic_receiver,
             waiting: false,
+            profile: None,
         }
     }
 
+    /// Sets the `profile` which is selected when the server is built with `self`.
+    #[inline]
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
     /// Emits a `message` of the given `kind` to the window.
     #[inline]
     fn emit<T>(&self, kind: &'static str, message: &T)
@@ -200,17 +217,22 @@ impl User {
         self.mnemonic_receiver.load_selection().await
     }
 
-    /// Requests mnemonic from user
+    /// Requests mnemonic and optional passphrase from user
     #[inline]
-    async fn request_mnemonic(&mut self) -> Mnemonic {
+    async fn request_mnemonic(&mut self) -> RecoveryPhrase {
         self.mnemonic_receiver.load_mnemonic().await
     }
 
     /// Sends validation message when password was correctly matched.
+    ///
+    /// Nothing is sent if the UI was already told to retry, which happens when the password is
+    /// locked out.
     #[inline]
     async fn validate_password(&mut self) {
-        self.waiting = false;
-        self.password_receiver.send_retry(false).await;
+        if self.waiting {
+            self.waiting = false;
+            self.password_receiver.send_retry(false).await;
+        }
     }
 }
 
@@ -220,6 +242,13 @@ impl Authorizer for User {
         Box::pin(async move { self.request_password().await })
     }
 
+    #[inline]
+    fn select_profile(&mut self, profiles: Vec<String>, current: String) -> ProfileFuture {
+        let _ = (profiles, current);
+        let profile = self.profile.take();
+        Box::pin(async move { profile })
+    }
+
     #[inline]
     fn setup(&mut self, data_exists: bool) -> SetupFuture {
         let window = self.window.clone();
@@ -229,38 +258,80 @@ impl Authorizer for User {
             } else {
                 // Mnemonic created here
                 // creating a new mnemonic in case user will create a new account.
-                Setup::CreateAccount(sample_mnemonic())
+                Setup::create_account(sample_mnemonic())
             };
 
-            while_w_timeout!(
-                {
-                    if APP_STATE.get_ui_connected() {
-                        break;
+            if APP_STATE.get_ui_connected() {
+                // NOTE: The UI is already connected when the setup runs again after a setup
+                //       failure, so it only needs the new payload.
+                window
+                    .emit("connect", payload.clone())
+                    .expect("The `connect` command failed to be emitted to the window.");
+            } else {
+                while_w_timeout!(
+                    {
+                        if APP_STATE.get_ui_connected() {
+                            break;
+                        }
+                        window
+                            .emit("connect", payload.clone())
+                            .expect("The `connect` command failed to be emitted to the window.");
+                    },
+                    5000,
+                    {
+                        panic!("Connection attempt timedout!");
                     }
-                    window
-                        .emit("connect", payload.clone())
-                        .expect("The `connect` command failed to be emitted to the window.");
-                },
-                5000,
-                {
-                    panic!("Connection attempt timedout!");
-                }
-            );
+                );
+            }
 
             let user_selection = self.request_selection().await;
 
-            match user_selection {
-                UserSelection::Create => payload,
-                UserSelection::SignIn => Setup::Login,
-                UserSelection::Recover => {
+            match (user_selection, payload) {
+                (UserSelection::Create(passphrase), Setup::CreateAccount { mnemonic, .. }) => {
+                    Setup::CreateAccount {
+                        mnemonic,
+                        passphrase,
+                    }
+                }
+                (UserSelection::Create(_), payload) => payload,
+                (UserSelection::SignIn, _) => Setup::Login,
+                (UserSelection::ImportBackup { path, password }, _) => {
+                    Setup::ImportBackup { path, password }
+                }
+                (UserSelection::Recover, _) => {
                     // if user decides to recover an existing account we need to stall and wait for their seed phrase.
-                    let user_seed_phrase = self.request_mnemonic().await;
-                    Setup::CreateAccount(user_seed_phrase)
+                    let (mnemonic, passphrase) = self.request_mnemonic().await;
+                    Setup::CreateAccount {
+                        mnemonic,
+                        passphrase,
+                    }
                 }
             }
         })
     }
 
+    #[inline]
+    fn setup_failure(&mut self, failure: &SetupFailure) -> RecoveryFuture {
+        let failure = failure.clone();
+        Box::pin(async move {
+            // NOTE: The UI may still be waiting to hear whether its password was accepted.
+            if self.waiting {
+                self.waiting = false;
+                self.password_receiver.send_retry(true).await;
+            }
+            self.window.show().expect("Unable to show window.");
+            self.emit("setup_failure", &failure);
+            self.mnemonic_receiver.load_recovery_choice().await
+        })
+    }
+
+    #[inline]
+    fn state_divergence(&mut self, divergence: &StateDivergence) -> DivergenceFuture {
+        self.window.show().expect("Unable to show window.");
+        self.emit("state_divergence", divergence);
+        Box::pin(async move { self.mnemonic_receiver.load_divergence_choice().await })
+    }
+
     #[inline]
     fn wake<T>(&mut self, prompt: &T) -> UnitFuture
     where
@@ -272,6 +343,19 @@ impl Authorizer for User {
         Box::pin(async move {})
     }
 
+    #[inline]
+    fn password_delay(&mut self, delay: &PasswordDelay) -> UnitFuture {
+        let delay = *delay;
+        Box::pin(async move {
+            // NOTE: The UI is told that its password was wrong before it is told how long to wait.
+            if self.waiting {
+                self.waiting = false;
+                self.password_receiver.send_retry(true).await;
+            }
+            self.emit("password_delay", &delay);
+        })
+    }
+
     #[inline]
     fn sleep(&mut self) -> UnitFuture {
         APP_STATE.set_authorizing(false);
@@ -295,6 +379,11 @@ pub type AppHandleStore = Store<AppHandle>;
 /// Abort Handle Store
 pub type AbortHandleStore = Store<JoinHandle<()>>;
 
+/// Profile Store
+///
+/// Holds the profile requested by [`switch_profile`] until the restarted server selects it.
+pub type ProfileStore = Store<String>;
+
 /// Called from the UI after it recieves a `connect` event.
 ///
 /// To ensure proper connection you should emit `connect` continuously until the
@@ -398,32 +487,72 @@ async fn stop_password_prompt(password_store: State<'_, PasswordStore>) -> Resul
     Ok(())
 }
 
-/// Sends the current `mnemonic` into storage from the UI.
+/// Validates the recovery phrase `mnemonic` typed into the UI, returning the wordlist it is
+/// written in.
+#[tauri::command]
+async fn validate_mnemonic(mnemonic: String) -> Result<Wordlist, MnemonicError> {
+    mnemonic::validate(&mnemonic)
+}
+
+/// Sends the current `mnemonic` and optional BIP39 `passphrase` into storage from the UI.
 #[tauri::command]
 async fn send_mnemonic(
     mnemonic_store: State<'_, MnemonicStore>,
     mnemonic: String,
-) -> Result<(), ()> {
-    // NOTE: Mnemonic is assumed to be valid because it is validated by front end bip39 library.
+    passphrase: Option<String>,
+) -> Result<(), MnemonicError> {
+    let recovered_mnemonic = mnemonic::parse(&mnemonic)?;
+    if let Some(store) = &mut *mnemonic_store.lock().await {
+        store
+            .load_exact(
+                recovered_mnemonic,
+                passphrase.filter(|p| !p.is_empty()).map(SecretString::new),
+            )
+            .await;
+    }
+    Ok(())
+}
+
+/// Combines the recovery phrase `shares` and sends the recovered mnemonic with the optional BIP39
+/// `passphrase` into storage from the UI.
+#[tauri::command]
+async fn send_mnemonic_shares(
+    mnemonic_store: State<'_, MnemonicStore>,
+    shares: Vec<String>,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    let recovered_mnemonic = shares::combine_mnemonic(&shares).map_err(|err| err.to_string())?;
     if let Some(store) = &mut *mnemonic_store.lock().await {
-        let recovered_mnemonic =
-            Mnemonic::new(mnemonic.as_str()).expect("Unable to generate recovered Mnemonic.");
-        store.load_exact(recovered_mnemonic).await;
+        store
+            .load_exact(
+                recovered_mnemonic,
+                passphrase.filter(|p| !p.is_empty()).map(SecretString::new),
+            )
+            .await;
     }
     Ok(())
 }
 
 /// Sets the user's selection of whether to create a new account, login, or recover
-/// using a seed phrase.
+/// using a seed phrase. New accounts can be protected with an optional BIP39 `passphrase`, and
+/// accounts imported from a backup need the `backup_path` and `backup_password`.
 #[tauri::command]
 async fn user_selection(
     mnemonic_store: State<'_, MnemonicStore>,
     selection: String,
+    passphrase: Option<String>,
+    backup_path: Option<String>,
+    backup_password: Option<String>,
 ) -> Result<(), ()> {
     let selected_option = if selection == "Create" {
-        UserSelection::Create
+        UserSelection::Create(passphrase.filter(|p| !p.is_empty()).map(SecretString::new))
     } else if selection == "Recover" {
         UserSelection::Recover
+    } else if selection == "ImportBackup" {
+        UserSelection::ImportBackup {
+            path: backup_path.ok_or(())?.into(),
+            password: SecretString::new(backup_password.ok_or(())?),
+        }
     } else {
         UserSelection::SignIn
     };
@@ -435,24 +564,65 @@ async fn user_selection(
     Ok(())
 }
 
+/// Sets the user's `choice` of how to recover from a failure while setting up the signer.
+#[tauri::command]
+async fn resolve_setup_failure(
+    mnemonic_store: State<'_, MnemonicStore>,
+    choice: RecoveryChoice,
+) -> Result<(), ()> {
+    if let Some(store) = &mut *mnemonic_store.lock().await {
+        store.load_recovery_choice(choice).await;
+    }
+    Ok(())
+}
+
+/// Sets the user's choice of whether to `repair` a signer state which diverges from the wallet or
+/// to keep it.
+#[tauri::command]
+async fn resolve_state_divergence(
+    mnemonic_store: State<'_, MnemonicStore>,
+    repair: bool,
+) -> Result<(), ()> {
+    let choice = if repair {
+        DivergenceChoice::Repair
+    } else {
+        DivergenceChoice::Keep
+    };
+    if let Some(store) = &mut *mnemonic_store.lock().await {
+        store.load_divergence_choice(choice).await;
+    }
+    Ok(())
+}
+
 /// Checks for storage file and backup file and deletes it if it exists respectively
 /// for the seleceted network.
-async fn check_and_delete_files(network: Network, config: &Config) -> Result<(), ()> {
-    if let Ok(metadata) = fs::metadata(config.data_path[network].clone()).await {
+async fn check_and_delete_files(network: &NetworkId, config: &Config) -> Result<(), ()> {
+    let data_file = config.data_file(network);
+    if let Ok(metadata) = fs::metadata(&data_file).await {
         if metadata.is_file() {
-            fs::remove_file(config.data_path[network].clone())
+            fs::remove_file(data_file)
                 .await
                 .expect("Unable to delete storage file.");
         }
     }
 
-    if let Ok(metadata) = fs::metadata(config.backup_data_path[network].clone()).await {
+    let backup_data_file = config.backup_data_file(network);
+    if let Ok(metadata) = fs::metadata(&backup_data_file).await {
         if metadata.is_file() {
-            fs::remove_file(config.backup_data_path[network].clone())
+            fs::remove_file(backup_data_file)
                 .await
                 .expect("Unable to delete backup file.");
         }
     }
+
+    let history_file = config.history_file(network);
+    if let Ok(metadata) = fs::metadata(&history_file).await {
+        if metadata.is_file() {
+            fs::remove_file(history_file)
+                .await
+                .expect("Unable to delete history file.");
+        }
+    }
     Ok(())
 }
 
@@ -473,8 +643,9 @@ async fn reset_account(
     abort_handle_store: State<'_, AbortHandleStore>,
     password_store: State<'_, PasswordStore>,
     mnemonic_store: State<'_, MnemonicStore>,
+    profile_store: State<'_, ProfileStore>,
 ) -> Result<(), ()> {
-    let config =
+    let mut config =
         Config::try_default().expect("Unable to generate the default server configuration.");
 
     // first we kill the currently running server instance, so that the files stop being modified
@@ -488,15 +659,26 @@ async fn reset_account(
         // the user might delete an account during sync. In this case we also need to check
         // to delete backup files, if they exist aswell.
 
-        check_and_delete_files(Network::Dolphin, &config)
-            .await
-            .expect("Unable to delete Dolphin files");
-        check_and_delete_files(Network::Calamari, &config)
-            .await
-            .expect("Unable to delete Calamari files");
-        check_and_delete_files(Network::Manta, &config)
-            .await
-            .expect("Unable to delete Manta files");
+        for network in config.networks() {
+            check_and_delete_files(&network, &config)
+                .await
+                .unwrap_or_else(|_| panic!("Unable to delete {network} files"));
+        }
+
+        for path in [
+            &config.wallet_path,
+            &config.address_book_path,
+            &config.kdf_header_path,
+            &config.password_attempts_path,
+        ] {
+            if let Ok(metadata) = fs::metadata(path).await {
+                if metadata.is_file() {
+                    fs::remove_file(path)
+                        .await
+                        .expect("Unable to delete wallet file.");
+                }
+            }
+        }
     }
 
     let app_handle_guard = app_handle_store.lock().await;
@@ -512,20 +694,26 @@ async fn reset_account(
     password_store.set(password_sender).await;
     mnemonic_store.set(mnemonic_sender).await;
 
+    use_bundled_proving_directory(app_handle, &mut config);
     let tray_handle = app_handle.tray_handle();
     let new_window = app_handle
         .get_window("main")
         .expect("Unable to open option");
 
     let server_store_clone = app_handle.state::<ServerStore>().inner().clone();
+    let profile = profile_store.lock().await.take();
 
     let new_handle = spawn(async move {
-        let new_server = Server::build(
+        let new_server = match Server::build(
             config,
-            User::new(new_window, password_receiver, mnemonic_receiver),
+            User::new(new_window.clone(), password_receiver, mnemonic_receiver)
+                .with_profile(profile),
         )
         .await
-        .expect("Unable to build manta-signer");
+        {
+            Ok(server) => server,
+            Err(err) => return report_build_error(&new_window, err),
+        };
 
         server_store_clone.set(new_server.clone()).await;
 
@@ -552,18 +740,271 @@ async fn reset_account(
 #[tauri::command]
 async fn address(server_store: State<'_, ServerStore>) -> Result<String, ()> {
     if let Some(store) = &mut *server_store.lock().await {
+        let network = store.default_network();
         let key = store
-            .get_address(Message {
-                // TODO: do we need to passing network?
-                network: Network::Dolphin,
-                message: GetRequest::Get,
-            })
+            .get_address(AccountMessage::new(network, GetRequest::Get))
             .await;
         return key;
     }
     Err(())
 }
 
+/// Returns the number of failed password attempts since the last successful login to the front
+/// end.
+#[tauri::command]
+async fn failed_password_attempts(server_store: State<'_, ServerStore>) -> Result<u32, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return Ok(store.failed_password_attempts());
+    }
+    Err(())
+}
+
+/// Returns the enabled networks, including the custom networks, to the front end.
+#[tauri::command]
+fn networks() -> Result<Vec<NetworkId>, ()> {
+    Config::try_default()
+        .map(|config| config.enabled())
+        .ok_or(())
+}
+
+/// Returns the private balances held by the signer on `network` to the front end.
+#[tauri::command]
+async fn balances(
+    network: NetworkId,
+    server_store: State<'_, ServerStore>,
+) -> Result<BalanceResponse, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        let server = store.clone();
+        return server
+            .balances(NetworkMessage::new(network, GetRequest::Get))
+            .await
+            .map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Resets the synchronization state of the signer on `network` so that it resyncs from genesis.
+#[tauri::command]
+async fn reset_sync_state(
+    network: NetworkId,
+    server_store: State<'_, ServerStore>,
+) -> Result<(), ()> {
+    if let Some(store) = &*server_store.lock().await {
+        let server = store.clone();
+        return server
+            .reset_sync_state(NetworkMessage::new(network, GetRequest::Get))
+            .await
+            .map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Returns one page of the transaction history on `network` to the front end.
+#[tauri::command]
+async fn history(
+    network: NetworkId,
+    page: usize,
+    page_size: usize,
+    server_store: State<'_, ServerStore>,
+) -> Result<HistoryPage, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        let server = store.clone();
+        return server
+            .history(NetworkMessage::new(
+                network,
+                HistoryQuery { page, page_size },
+            ))
+            .await
+            .map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Exports the full transaction history on `network` in the given `format`.
+#[tauri::command]
+async fn export_history(
+    network: NetworkId,
+    format: ExportFormat,
+    server_store: State<'_, ServerStore>,
+) -> Result<String, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        let server = store.clone();
+        return server
+            .export_history(NetworkMessage::new(network, format))
+            .await
+            .map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Returns all the entries of the address book to the front end.
+#[tauri::command]
+async fn address_book(server_store: State<'_, ServerStore>) -> Result<Vec<AddressBookEntry>, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return Ok(store.address_book());
+    }
+    Err(())
+}
+
+/// Labels the base58-encoded `address` with `label` in the address book.
+#[tauri::command]
+async fn set_address_label(
+    address: String,
+    label: String,
+    server_store: State<'_, ServerStore>,
+) -> Result<(), ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return store
+            .set_address_label(address, label)
+            .await
+            .map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Removes the label of the base58-encoded `address` from the address book.
+#[tauri::command]
+async fn remove_address_label(
+    address: String,
+    server_store: State<'_, ServerStore>,
+) -> Result<bool, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return store.remove_address_label(&address).await.map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Returns all the assets registered in the signer for `network` to the front end.
+#[tauri::command]
+async fn registered_assets(
+    network: NetworkId,
+    server_store: State<'_, ServerStore>,
+) -> Result<Vec<RegisteredAsset>, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return Ok(store.registered_assets(&network));
+    }
+    Err(())
+}
+
+/// Adds the custom `asset` to the signer asset registry for `network`.
+#[tauri::command]
+async fn add_custom_asset(
+    network: NetworkId,
+    asset: RegisteredAsset,
+    server_store: State<'_, ServerStore>,
+) -> Result<bool, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return store
+            .add_custom_asset(&network, asset)
+            .await
+            .map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Removes the custom asset with the given `id` from the signer asset registry for `network`.
+#[tauri::command]
+async fn remove_custom_asset(
+    network: NetworkId,
+    id: u128,
+    server_store: State<'_, ServerStore>,
+) -> Result<bool, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return store
+            .remove_custom_asset(&network, id)
+            .await
+            .map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Returns the accounts of the wallet to the front end.
+#[tauri::command]
+async fn accounts(server_store: State<'_, ServerStore>) -> Result<Vec<AccountInfo>, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return store.accounts().map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Creates a new account in the wallet with the optional `label`, returning its index.
+#[tauri::command]
+async fn create_account(
+    label: Option<String>,
+    server_store: State<'_, ServerStore>,
+) -> Result<u32, ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return store.create_account(label).await.map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Sets the `label` of the account with the given `index`.
+#[tauri::command]
+async fn set_account_label(
+    index: u32,
+    label: Option<String>,
+    server_store: State<'_, ServerStore>,
+) -> Result<(), ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return store.set_account_label(index, label).await.map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Selects the account with the given `index`.
+#[tauri::command]
+async fn select_account(index: u32, server_store: State<'_, ServerStore>) -> Result<(), ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return store.select_account(index).await.map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Returns the name of the selected profile to the front end.
+///
+/// This is read from the configuration so that it is available before the user signs in.
+#[tauri::command]
+fn current_profile() -> Result<String, ()> {
+    Config::try_default().map(|config| config.profile).ok_or(())
+}
+
+/// Returns the names of all the profiles to the front end.
+#[tauri::command]
+async fn profiles() -> Result<Vec<String>, ()> {
+    let config = Config::try_default().ok_or(())?;
+    config.profiles().await.map_err(|_| ())
+}
+
+/// Switches to the profile with the given `name`, creating it if it does not exist yet, and
+/// restarts the server so that the user can sign in to it. The restarted server selects the
+/// profile through [`Authorizer::select_profile`], which also makes it the selected profile of
+/// the next launch. The files of the other profiles are left untouched.
+#[tauri::command]
+async fn switch_profile(
+    name: String,
+    app_handle_store: State<'_, AppHandleStore>,
+    abort_handle_store: State<'_, AbortHandleStore>,
+    password_store: State<'_, PasswordStore>,
+    mnemonic_store: State<'_, MnemonicStore>,
+    profile_store: State<'_, ProfileStore>,
+) -> Result<(), ()> {
+    Config::try_default()
+        .and_then(|config| config.with_profile(&name))
+        .ok_or(())?;
+    profile_store.set(name).await;
+    reset_account(
+        false,
+        false,
+        app_handle_store,
+        abort_handle_store,
+        password_store,
+        mnemonic_store,
+        profile_store,
+    )
+    .await
+}
+
 /// Exports the user recovery phrase upon successful password match.
 #[tauri::command]
 async fn get_recovery_phrase(
@@ -571,7 +1012,8 @@ async fn get_recovery_phrase(
     server_store: State<'_, ServerStore>,
 ) -> Result<Mnemonic, ()> {
     if let Some(store) = &mut *server_store.lock().await {
-        match store.get_stored_mnemonic(Network::Dolphin, &prompt).await {
+        let network = store.default_network();
+        match store.get_stored_mnemonic(&network, &prompt).await {
             Ok(mnemonic) => Ok(mnemonic),
             Err(_) => Err(()),
         }
@@ -580,6 +1022,42 @@ async fn get_recovery_phrase(
     }
 }
 
+/// Returns the recovery phrase split into `count` shares, any `threshold` of which recover it.
+#[tauri::command]
+async fn get_recovery_shares(
+    prompt: String,
+    threshold: u8,
+    count: u8,
+    server_store: State<'_, ServerStore>,
+) -> Result<Vec<String>, ()> {
+    if let Some(store) = &mut *server_store.lock().await {
+        let network = store.default_network();
+        return store
+            .get_stored_shares(&network, &prompt, threshold, count)
+            .await
+            .map_err(|_| ());
+    }
+    Err(())
+}
+
+/// Writes a portable backup of the wallet to `path`, encrypted under `backup_password`, after the
+/// user authorizes it with their wallet password.
+#[tauri::command]
+async fn export_backup(
+    path: String,
+    backup_password: String,
+    prompt: String,
+    server_store: State<'_, ServerStore>,
+) -> Result<(), ()> {
+    if let Some(store) = &*server_store.lock().await {
+        return store
+            .export_backup(path.as_ref(), &prompt, SecretString::new(backup_password))
+            .await
+            .map_err(|_| ());
+    }
+    Err(())
+}
+
 /// Cancels the current signing transaction within the server, allowing for
 /// new signing transactions to be sent.
 #[tauri::command]
@@ -590,6 +1068,23 @@ async fn cancel_sign(server_store: State<'_, ServerStore>) -> Result<(), ()> {
     Ok(())
 }
 
+/// Points `config` to the proving contexts bundled in the resources of the application.
+#[inline]
+fn use_bundled_proving_directory(app_handle: &AppHandle, config: &mut Config) {
+    if let Some(directory) = app_handle.path_resolver().resolve_resource("proving") {
+        config.use_bundled_proving_directory(directory);
+    }
+}
+
+/// Shows `window` and reports the `err` that stopped the signer from being built.
+#[inline]
+fn report_build_error(window: &Window, err: Error) {
+    window.show().expect("Unable to show window.");
+    window
+        .emit("signer_error", err.to_string())
+        .expect("The `signer_error` event failed to be emitted to the window.");
+}
+
 /// Returns the window with the given `label` from `app`.
 ///
 /// # Panics
@@ -609,8 +1104,8 @@ where
 
 /// Runs the main Tauri application.
 fn main() {
-    let config =
-        Config::try_default().expect("Unable to generate the default server configuration.");
+    let mut config = Config::load()
+        .unwrap_or_else(|err| panic!("Unable to load the signer configuration: {err}"));
 
     let mut app = tauri::Builder::default()
         .system_tray(
@@ -650,6 +1145,7 @@ fn main() {
         .manage(MnemonicStore::default())
         .manage(AppHandleStore::default())
         .manage(AbortHandleStore::default())
+        .manage(ProfileStore::default())
         .setup(|app| {
             let window = window(app, "main");
             let password_store = app.state::<PasswordStore>().inner().clone();
@@ -658,17 +1154,19 @@ fn main() {
             let app_handle_store = app.state::<AppHandleStore>().inner().clone();
             let abort_handle = app.state::<AbortHandleStore>().inner().clone();
             let app_handle = app.handle();
+            use_bundled_proving_directory(&app_handle, &mut config);
 
             let join_handle = spawn(async move {
                 let (password_sender, password_receiver) = password_channel();
                 let (mnemonic_sender, mnemonic_receiver) = mnemonic_channel();
-                let user = User::new(window, password_receiver, mnemonic_receiver);
+                let user = User::new(window.clone(), password_receiver, mnemonic_receiver);
                 password_store.set(password_sender).await;
                 mnemonic_store.set(mnemonic_sender).await;
                 app_handle_store.set(app_handle).await;
-                let server = Server::build(config, user)
-                    .await
-                    .expect("Unable to build manta-signer server.");
+                let server = match Server::build(config, user).await {
+                    Ok(server) => server,
+                    Err(err) => return report_build_error(&window, err),
+                };
                 server_store.set(server.clone()).await;
                 server
                     .start()
@@ -686,13 +1184,38 @@ fn main() {
             send_password,
             stop_password_prompt,
             user_selection,
+            resolve_setup_failure,
+            resolve_state_divergence,
+            validate_mnemonic,
             send_mnemonic,
+            send_mnemonic_shares,
             reset_account,
             connect_ui,
             disconnect_ui,
             set_signer_ready,
             address,
+            failed_password_attempts,
+            networks,
+            balances,
+            reset_sync_state,
+            history,
+            export_history,
+            address_book,
+            set_address_label,
+            remove_address_label,
+            accounts,
+            create_account,
+            set_account_label,
+            select_account,
+            current_profile,
+            profiles,
+            switch_profile,
+            registered_assets,
+            add_custom_asset,
+            remove_custom_asset,
             get_recovery_phrase,
+            get_recovery_shares,
+            export_backup,
             cancel_sign,
             enable_reset_menu_item
         ])
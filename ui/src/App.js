@@ -7,6 +7,9 @@ import Reset from "./pages/Reset";
 import CreateOrRecover from './pages/CreateOrRecover';
 import Recover from './pages/Recovery/Recover';
 import ViewSecretPhrase from './pages/ViewPhrase/ViewSecretPhrase';
+import SignerError from './pages/SignerError';
+import StateDivergence from './pages/StateDivergence';
+import SetupFailure from './pages/SetupFailure';
 import { Container } from 'semantic-ui-react';
 import { appWindow } from '@tauri-apps/api/window';
 import { invoke } from '@tauri-apps/api/tauri';
@@ -19,8 +22,11 @@ import Finish from './pages/Recovery/Finish';
 import ShowPhrase from './pages/AccountCreation/ShowPhrase';
 import ConfirmPhrase from './pages/AccountCreation/ConfirmPhrase';
 
-const SEND = "Send";
-const PUBLIC = "Public";
+const PRIVATE_TRANSFER = "PrivateTransfer";
+const TO_PUBLIC = "ToPublic";
+const ACTION_PROMPTS = {
+  ResetSyncState: "Reset the synchronization state, which resyncs the signer from genesis",
+};
 const GET_RECOVERY_PHRASE = "GetRecoveryPhrase";
 
 function App() {
@@ -39,11 +45,24 @@ function App() {
     authorize: false,
     connect: false,
     tray_reset_account: false,
+    signer_error: false,
+    state_divergence: false,
+    setup_failure: false,
+    password_delay: false,
     show_secret_phrase: false,
     show_zk_address: false
   });
   const [exportedSecretPhrase, setExportedSecretPhrase] = useState(null);
   const [loginFailedOccured, setLoginFailedOccured] = useState(false);
+  const [signerError, setSignerError] = useState(null);
+  const [stateDivergence, setStateDivergence] = useState(null);
+  const [setupFailure, setSetupFailure] = useState(null);
+  const [passwordDelay, setPasswordDelay] = useState(null);
+  const [profiles, setProfiles] = useState(null);
+  const [currentProfile, setCurrentProfile] = useState(null);
+
+  // page to go back to once the user resolved a diverging signer state.
+  const divergenceReturnPathRef = useRef(null);
 
   // keeps show secret phrase listener in sync with exportingPhrase state
   // whether or not we are currently exporting the phrase.
@@ -62,17 +81,18 @@ function App() {
         invoke('connect_ui');
         console.log("[INFO]: Connect Event: ", event);
         let payload = event.payload;
+        loadProfiles();
 
         // We don't want to switch the page on reset during recovery process.
         if (pathnameRef.current === ("/recover/loading")) {
           setPayloadType('CreateAccount');
-          setRecoveryPhrase(payload.content);
+          setRecoveryPhrase(payload.content.mnemonic);
           return;
         }
 
         switch (payload.type) {
           case 'CreateAccount':
-            setRecoveryPhrase(payload.content);
+            setRecoveryPhrase(payload.content.mnemonic);
             setPayloadType('CreateAccount');
             navigate("/create-or-recover");
             break;
@@ -96,6 +116,46 @@ function App() {
       })
     }
 
+    const listenForSignerErrors = async () => {
+      console.log("[INFO]: Setup signer error listener.");
+      listen('signer_error', (event) => {
+        console.log("[ERROR]: Signer Error: ", event);
+        setSignerError(event.payload);
+        navigate("/error");
+      })
+    }
+
+    const listenForStateDivergence = async () => {
+      console.log("[INFO]: Setup state divergence listener.");
+      listen('state_divergence', (event) => {
+        console.log("[WARNING]: State Divergence: ", event);
+        divergenceReturnPathRef.current = pathnameRef.current;
+        setStateDivergence(event.payload);
+        navigate("/state-divergence");
+      })
+    }
+
+    const listenForSetupFailures = async () => {
+      console.log("[INFO]: Setup setup failure listener.");
+      listen('setup_failure', (event) => {
+        console.log("[ERROR]: Setup Failure: ", event);
+        setSetupFailure(event.payload);
+        navigate("/setup-failure");
+      })
+    }
+
+    const listenForPasswordDelays = async () => {
+      console.log("[INFO]: Setup password delay listener.");
+      listen('password_delay', (event) => {
+        console.log("[WARNING]: Password Delay: ", event);
+        setPasswordDelay(event.payload);
+        if (event.payload.lockout) {
+          // Passwords are checked again once the lockout is over.
+          setTimeout(() => setPasswordDelay(null), event.payload.seconds * 1000);
+        }
+      })
+    }
+
     if (!activeListeners.connect) {
       beginInitialConnectionPhase();
       setActiveListeners({
@@ -110,6 +170,34 @@ function App() {
         tray_reset_account: true,
       });
     }
+    if (!activeListeners.setup_failure) {
+      listenForSetupFailures();
+      setActiveListeners((listeners) => ({
+        ...listeners,
+        setup_failure: true,
+      }));
+    }
+    if (!activeListeners.password_delay) {
+      listenForPasswordDelays();
+      setActiveListeners((listeners) => ({
+        ...listeners,
+        password_delay: true,
+      }));
+    }
+    if (!activeListeners.state_divergence) {
+      listenForStateDivergence();
+      setActiveListeners((listeners) => ({
+        ...listeners,
+        state_divergence: true,
+      }));
+    }
+    if (!activeListeners.signer_error) {
+      listenForSignerErrors();
+      setActiveListeners((listeners) => ({
+        ...listeners,
+        signer_error: true,
+      }));
+    }
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [isConnected, activeListeners, pathnameRef, navigate]);
 
@@ -119,34 +207,43 @@ function App() {
     navigate("/loading");
   };
 
-  // This function parses the transaction summary message depending
-  // on the type of transaction: TransferShape::PrivateTransfer, TransferShape::Reclaim
-  const parseTransactionSummary = (summary) => {
+  // Formats the raw `value` of an asset with the given number of `decimals`, which are `null` if
+  // the asset is not fungible or was not described by the requesting site.
+  const formatAssetValue = (value, decimals) => {
+    if (decimals === null || decimals === undefined || decimals === 0) {
+      return value;
+    }
+    const padded = value.padStart(decimals + 1, "0");
+    const whole = padded.slice(0, padded.length - decimals);
+    const fraction = padded.slice(padded.length - decimals).replace(/0+$/, "");
+    return fraction.length > 0 ? `${whole}.${fraction}` : whole;
+  }
+
+  // Builds the summary shown on the authorization page from the structured fields of an
+  // `AuthorizationRequest`, depending on its transfer shape.
+  const buildTransactionSummary = (request) => {
 
     let parsedAuthorizationSummary = {
-      sendAmount: parseFloat(summary[1]),
-      currency: summary[2],
+      prompt: null,
+      sendAmount: formatAssetValue(request.value, request.decimals),
+      currency: request.symbol !== null ? request.symbol : `units of asset ${request.asset_id}`,
       toAddress: null,
-      network: null
+      network: request.network,
+      firstTimeRecipient: false,
+      warnings: request.warnings
     };
 
-    if (summary[0] === SEND) {
-
-      // Send {} to {} on {} network
-      let toAddress = summary[4];
-      toAddress = toAddress.substr(0, 10)
-        + "..." + toAddress.substr(toAddress.length - 10);
-      parsedAuthorizationSummary.toAddress = toAddress;
-      parsedAuthorizationSummary.network = summary[6];
-    } else if (summary[0] === PUBLIC) {
-
-      // Public {} on {} network
+    if (request.shape === PRIVATE_TRANSFER) {
+      const address = request.recipient.address;
+      const abbreviatedAddress = address.substr(0, 10) + "..." + address.substr(address.length - 10);
+      parsedAuthorizationSummary.toAddress = request.recipient.label !== null
+        ? `${request.recipient.label} (${abbreviatedAddress})`
+        : abbreviatedAddress;
+      parsedAuthorizationSummary.firstTimeRecipient = request.recipient.is_new;
+    } else if (request.shape === TO_PUBLIC) {
       parsedAuthorizationSummary.toAddress = "Your Public Address";
-      parsedAuthorizationSummary.network = summary[4];
     } else {
-      // Privatize {} on {} network
       parsedAuthorizationSummary.toAddress = "Your Private zkAddress";
-      parsedAuthorizationSummary.network = summary[4];
     }
 
     return parsedAuthorizationSummary;
@@ -165,12 +262,35 @@ function App() {
         return;
       }
 
-      // Case 2: we need authorization for signing a transaction.
-      console.log("[INFO]: Authorization for transaction");
       await checkAndStopExportingPhrase();
-      let parsedAuthorizationSummary = parseTransactionSummary(event.payload.split(" "));
 
-      setAuthorizationSummary(parsedAuthorizationSummary);
+      // Case 2: we need authorization for another plain-text prompt, which is shown as it is.
+      if (typeof event.payload === 'string') {
+        console.log("[INFO]: Authorization for prompt");
+        setAuthorizationSummary({ prompt: event.payload, warnings: [] });
+        navigate("/authorize");
+        appWindow.show();
+        return;
+      }
+
+      // Case 3: we need authorization for an action which does not sign a transaction,
+      // described by an `ActionRequest`.
+      if (event.payload.action) {
+        console.log("[INFO]: Authorization for action: ", event.payload.action);
+        setAuthorizationSummary({
+          prompt: ACTION_PROMPTS[event.payload.action] || event.payload.summary,
+          network: event.payload.network,
+          warnings: []
+        });
+        navigate("/authorize");
+        appWindow.show();
+        return;
+      }
+
+      // Case 4: we need authorization for signing a transaction, described by the structured
+      // fields of an `AuthorizationRequest`.
+      console.log("[INFO]: Authorization for transaction");
+      setAuthorizationSummary(buildTransactionSummary(event.payload));
       navigate("/authorize");
       appWindow.show();
     });
@@ -202,6 +322,25 @@ function App() {
     })
   }
 
+  // The signer sets itself up again after the choice, so it tells us which page to show next.
+  const resolveSetupFailure = async (choice) => {
+    console.log("[INFO]: Resolving setup failure with: ", choice);
+    setLoginFailedOccured(false);
+    setSetupFailure(null);
+    navigate("/loading");
+    await invoke('resolve_setup_failure', { choice: choice });
+  }
+
+  const resolveStateDivergence = async (repair) => {
+    console.log("[INFO]: Resolving state divergence, repair: ", repair);
+    await invoke('resolve_state_divergence', { repair: repair });
+    setStateDivergence(null);
+    navigate(divergenceReturnPathRef.current || "/loading");
+    if (isConnected) {
+      hideWindow();
+    }
+  }
+
   const getSecretRecoveryPhrase = async () => {
 
     if (exportingPhraseRef.current) {
@@ -224,9 +363,9 @@ function App() {
     }
   }
 
-  const sendSelection = async (selection) => {
+  const sendSelection = async (selection, passphrase = null) => {
     console.log("[INFO]: Send selection to signer server.");
-    return await invoke('user_selection', { selection: selection });
+    return await invoke('user_selection', { selection: selection, passphrase: passphrase });
   }
 
   const sendPassword = async (password) => {
@@ -234,9 +373,9 @@ function App() {
     return await invoke('send_password', { password: password });
   };
 
-  const sendMnemonic = async (mnemonic) => {
+  const sendMnemonic = async (mnemonic, passphrase = null) => {
     console.log("[INFO]: Send mnemonic to signer server.");
-    return await invoke('send_mnemonic', { mnemonic: mnemonic })
+    return await invoke('send_mnemonic', { mnemonic: mnemonic, passphrase: passphrase })
   }
 
   const stopPasswordPrompt = async () => {
@@ -244,6 +383,26 @@ function App() {
     await invoke('stop_password_prompt');
   };
 
+  const loadProfiles = async () => {
+    try {
+      setProfiles(await invoke('profiles'));
+      setCurrentProfile(await invoke('current_profile'));
+    } catch (error) {
+      console.log("[WARNING]: Failed loading profiles.");
+    }
+  }
+
+  const switchProfile = async (name) => {
+    console.log("[INFO]: Switching to profile: " + name);
+    await endConnection();
+    try {
+      await invoke('switch_profile', { name: name });
+    } catch (error) {
+      console.log("[WARNING]: Invalid profile name: " + name);
+      await invoke('reset_account', { delete: false, restart: false });
+    }
+  }
+
   const restartServer = async (deleteAccount, restartApp) => {
     console.log("[INFO]: Restarting Server with current parameters: "
       + "Delete Account: " + deleteAccount + " Restart App: " + restartApp
@@ -330,6 +489,11 @@ function App() {
     setReceivingKeyDisplay(newReceivingKeyDisplay);
   }
 
+  const getFailedPasswordAttempts = async () => {
+    console.log("[INFO]: Getting failed password attempts.")
+    return await invoke('failed_password_attempts');
+  }
+
   const cancelReset = async () => {
     navigate(-1);
   }
@@ -344,10 +508,30 @@ function App() {
               isConnected={isConnected}
             />
           } />
+          <Route path='/error' element={
+            <SignerError
+              signerError={signerError}
+            />
+          } />
+          <Route path='/setup-failure' element={
+            <SetupFailure
+              setupFailure={setupFailure}
+              resolveSetupFailure={resolveSetupFailure}
+            />
+          } />
+          <Route path='/state-divergence' element={
+            <StateDivergence
+              stateDivergence={stateDivergence}
+              resolveStateDivergence={resolveStateDivergence}
+            />
+          } />
           <Route path='/create-or-recover' element={
             <CreateOrRecover
               startCreate={startCreate}
               startRecover={startRecover}
+              profiles={profiles}
+              currentProfile={currentProfile}
+              switchProfile={switchProfile}
             />
           } />
           <Route path='/reset' element={
@@ -394,6 +578,7 @@ function App() {
               setSignerReady={setSignerReady}
               sendSelection={sendSelection}
               getReceivingKeys={getReceivingKeys}
+              getFailedPasswordAttempts={getFailedPasswordAttempts}
               receivingKey={receivingKey}
               receivingKeyDisplay={receivingKeyDisplay}
               sendPassword={sendPassword}
@@ -402,6 +587,11 @@ function App() {
               hideWindow={hideWindow}
               loginFailedOccured={loginFailedOccured}
               setLoginFailedOccured={setLoginFailedOccured}
+              passwordDelay={passwordDelay}
+              setPasswordDelay={setPasswordDelay}
+              profiles={profiles}
+              currentProfile={currentProfile}
+              switchProfile={switchProfile}
             />
           } />
           <Route path='/authorize' element={
@@ -411,6 +601,8 @@ function App() {
               sendPassword={sendPassword}
               stopPasswordPrompt={stopPasswordPrompt}
               hideWindow={hideWindow}
+              passwordDelay={passwordDelay}
+              setPasswordDelay={setPasswordDelay}
             />} />
           <Route path='/view-secret-phrase' element={
             <ViewSecretPhrase
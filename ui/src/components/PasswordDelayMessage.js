@@ -0,0 +1,27 @@
+import "../App.css";
+
+const PasswordDelayMessage = ({
+  passwordDelay
+}) => {
+
+  if (!passwordDelay || passwordDelay.seconds <= 0) {
+    return null;
+  }
+
+  if (passwordDelay.lockout) {
+    const minutes = Math.ceil(passwordDelay.seconds / 60);
+    return (
+      <p className='sub-text'>
+        Too many failed attempts, passwords are rejected for another {minutes} minute{minutes === 1 ? '' : 's'}.
+      </p>
+    )
+  }
+
+  return (
+    <p className='sub-text'>
+      Too many failed attempts, the next password is checked in {passwordDelay.seconds} seconds.
+    </p>
+  )
+}
+
+export default PasswordDelayMessage;
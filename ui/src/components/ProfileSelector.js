@@ -0,0 +1,44 @@
+import { Dropdown } from 'semantic-ui-react';
+import "../App.css";
+
+const ProfileSelector = ({
+  profiles,
+  currentProfile,
+  switchProfile
+}) => {
+
+  if (!profiles || !currentProfile) {
+    return null;
+  }
+
+  const options = profiles.map((profile) => ({
+    key: profile,
+    text: profile,
+    value: profile
+  }));
+
+  // Typing a name which is not in the list creates a new profile with that name.
+  const onChangeProfile = async (_e, data) => {
+    if (data.value !== currentProfile) {
+      await switchProfile(data.value);
+    }
+  }
+
+  return (
+    <div>
+      <Dropdown
+        className="ui fluid dropdown compressed"
+        fluid
+        selection
+        search
+        allowAdditions
+        additionLabel="New profile: "
+        options={options}
+        value={currentProfile}
+        onChange={onChangeProfile}
+      />
+    </div>
+  )
+}
+
+export default ProfileSelector;
@@ -17,6 +17,8 @@ const CreateAccount = ({
 }) => {
   const [password, setPassword] = useState('');
   const [confirmPassword, setConfirmPassword] = useState('');
+  // optional BIP39 passphrase, needed again together with the recovery phrase to recover the wallet
+  const [passphrase, setPassphrase] = useState('');
   const [passwordsMatch, setPasswordsMatch] = useState(false);
   const [isValidPassword, setIsValidPassword] = useState(false);
   const [recoveryPhraseConfirmed, setRecoveryPhraseConfirmed] = useState(false);
@@ -165,9 +167,10 @@ const CreateAccount = ({
   // This function will be called after the user confirms their secret recovery phrase.
   const onClickCreateAccount = async () => {
     console.log("[INFO]: Creating account.");
-    await sendSelection("Create");
+    await sendSelection("Create", passphrase || null);
     await sendPassword(password);
     setPassword('');
+    setPassphrase('');
     navigate("/create-account/finish");
   };
 
@@ -232,6 +235,10 @@ const CreateAccount = ({
     setConfirmPassword(e.target.value);
   }
 
+  const onChangePassphrase = (e) => {
+    setPassphrase(e.target.value);
+  }
+
   return (
     <>
       <Outlet context={{
@@ -240,6 +247,7 @@ const CreateAccount = ({
         checkPasswords,
         onChangePassword,
         onChangeConfirmPassword,
+        onChangePassphrase,
         onClickConfirmRecoveryPhrase,
         onClickSelectWordButton,
         onClickCopyPhrase,
@@ -253,6 +261,7 @@ const CreateAccount = ({
         isValidPassword,
         passwordsMatch,
         password,
+        passphrase,
         showError
       }} />
     </>
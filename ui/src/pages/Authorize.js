@@ -1,6 +1,7 @@
 import { once } from '@tauri-apps/api/event';
 import React, { useState, useEffect } from 'react';
 import { Button, Input } from 'semantic-ui-react';
+import PasswordDelayMessage from '../components/PasswordDelayMessage';
 
 const Authorize = ({
   cancelSign,
@@ -8,6 +9,8 @@ const Authorize = ({
   sendPassword,
   stopPasswordPrompt,
   hideWindow,
+  passwordDelay,
+  setPasswordDelay,
 }) => {
   const [password, setPassword] = useState('');
   const [passwordInvalid, setPasswordInvalid] = useState(false);
@@ -22,6 +25,7 @@ const Authorize = ({
 
   const onClickAuthorize = async () => {
     console.log("[INFO]: Authorizing.");
+    setPasswordDelay(null);
     const shouldRetry = await sendPassword(password);
     if (!shouldRetry) {
       setPassword('');
@@ -36,7 +40,10 @@ const Authorize = ({
     console.log("[INFO]: Declining Transaction.");
     setPassword('');
     setPasswordInvalid(false)
-    await stopPasswordPrompt();
+    // The signer has already rejected the request when the password is locked out.
+    if (!passwordDelay || !passwordDelay.lockout) {
+      await stopPasswordPrompt();
+    }
     await cancelSign();
     hideWindow();
   };
@@ -49,24 +56,63 @@ const Authorize = ({
   return (
     <>
       <div className='auth-transaction-header'>
-        <h1 className='main-headline'>Authorize Transaction</h1>
+        <h1 className='main-headline'>
+          {summary.prompt ? "Authorize Request" : "Authorize Transaction"}
+        </h1>
       </div>
-      <div className='transaction-container'>
-        <div className='transaction-detail'>
-          <h5 className='transaction-description'>Send</h5>
-          <div>
-            <h5 className='transaction-value'>{summary.sendAmount + " " + summary.currency}</h5>
-          </div>
+      {summary.warnings && summary.warnings.map((warning, index) => (
+        <div className='transaction-detail' key={index}>
+          <h5 className='transaction-description'>Warning</h5>
+          <h5 className='transaction-value'>
+            {warning.type === 'UnknownAsset'
+              ? `Unknown asset ${warning.content.asset_id}, raw value ${warning.content.value}`
+              : warning.type === 'MetadataMismatch'
+                ? `Asset ${warning.content.asset_id} is registered as ${warning.content.registered.symbol}`
+                : warning.type === 'UnregisteredAsset'
+                  ? `Asset ${warning.content.asset_id} is not registered, its symbol ${warning.content.symbol} is unverified`
+                  : warning.type === 'SymbolCollision'
+                    ? `Asset ${warning.content.asset_id} is NOT ${warning.content.registered.symbol}, which is registered as asset ${warning.content.registered.id}`
+                    : warning.type}
+          </h5>
         </div>
-        <div className='transaction-detail'>
-          <h5 className='transaction-description'>To</h5>
-          <h5 className='transaction-value'>{summary.toAddress}</h5>
+      ))}
+      {summary.prompt &&
+        <div className='transaction-container'>
+          <div className={summary.network ? 'transaction-detail' : 'transaction-detail-padded'}>
+            <h5 className='transaction-value'>{summary.prompt}</h5>
+          </div>
+          {summary.network &&
+            <div className='transaction-detail-padded'>
+              <h5 className='transaction-description'>Network</h5>
+              <h5 className='transaction-value'>{summary.network}</h5>
+            </div>
+          }
         </div>
-        <div className='transaction-detail-padded'>
-          <h5 className='transaction-description'>Network</h5>
-          <h5 className='transaction-value'>{summary.network}</h5>
+      }
+      {!summary.prompt &&
+        <div className='transaction-container'>
+          <div className='transaction-detail'>
+            <h5 className='transaction-description'>Send</h5>
+            <div>
+              <h5 className='transaction-value'>{summary.sendAmount + " " + summary.currency}</h5>
+            </div>
+          </div>
+          <div className='transaction-detail'>
+            <h5 className='transaction-description'>To</h5>
+            <h5 className='transaction-value'>{summary.toAddress}</h5>
+          </div>
+          {summary.firstTimeRecipient && (
+            <div className='transaction-detail'>
+              <h5 className='transaction-description'>Warning</h5>
+              <h5 className='transaction-value'>First-time recipient</h5>
+            </div>
+          )}
+          <div className='transaction-detail-padded'>
+            <h5 className='transaction-description'>Network</h5>
+            <h5 className='transaction-value'>{summary.network}</h5>
+          </div>
         </div>
-      </div>
+      }
       <Input
         className='input ui password'
         type="password"
@@ -75,11 +121,16 @@ const Authorize = ({
         onChange={(e) => onChangePassword(e.target.value)}
         error={passwordInvalid}
       />
+      <PasswordDelayMessage passwordDelay={passwordDelay} />
       <div className='authButtonsContainer'>
         <Button className="button ui cancel" onClick={onClickDecline}>
           Cancel
         </Button>
-        <Button className="button ui first thin" onClick={onClickAuthorize}>
+        <Button
+          className="button ui first thin"
+          onClick={onClickAuthorize}
+          disabled={passwordDelay && passwordDelay.lockout}
+        >
           Authorize
         </Button>
       </div>
@@ -1,9 +1,13 @@
 import { Button } from 'semantic-ui-react';
 import mainLogo from "../icons/manta.png";
+import ProfileSelector from '../components/ProfileSelector';
 import "../App.css";
 const CreateOrRecover = ({
   startCreate,
-  startRecover
+  startRecover,
+  profiles,
+  currentProfile,
+  switchProfile
 }) => {
 
   const onClickStartCreate = async () => {
@@ -25,6 +29,12 @@ const CreateOrRecover = ({
       <p className='sub-text'>The signer is a secret manager and zero-knowledge proof generator.</p>
     </div>
 
+    <ProfileSelector
+      profiles={profiles}
+      currentProfile={currentProfile}
+      switchProfile={switchProfile}
+    />
+
     <div className='first-button-container'>
       <Button className="button ui first" onClick={onClickStartCreate}>Create New Wallet</Button>
     </div>
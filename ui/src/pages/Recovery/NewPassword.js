@@ -11,10 +11,12 @@ const NewPassword = () => {
     checkPasswords,
     onChangePassword,
     onChangeConfirmPassword,
+    onChangePassphrase,
     MIN_PASSWORD_LENGTH,
     isValidPassword,
     passwordsMatch,
     password,
+    passphrase,
     showError
   } = useOutletContext();
 
@@ -41,6 +43,21 @@ const NewPassword = () => {
           onChange={(e) => onChangeConfirmPassword(e)}
         />
       </div>
+      {onChangePassphrase ?
+        <div>
+          <Input
+            className='input ui password'
+            type="password"
+            placeholder="BIP39 passphrase (optional)"
+            value={passphrase}
+            onChange={(e) => onChangePassphrase(e)}
+          />
+          {passphrase.length > 0 ?
+            <p className='sub-text'>
+              You will need this passphrase together with your recovery phrase to recover your wallet.
+            </p> : null}
+        </div>
+        : null}
       {!isValidPassword && password.length > 0 && showError ?
         <>
           <ErrorLabel
@@ -44,6 +44,9 @@ const Recover = ({
   const [mnemonicsValidity, setMnemonicsValidity] = useState(false);
   const [validMnemonics, setValidMnemonics] = useState(null);
 
+  // optional BIP39 passphrase, which must match the one the wallet was created with
+  const [passphrase, setPassphrase] = useState('');
+
   // new passwords
   const [password, setPassword] = useState('');
   const [confirmPassword, setConfirmPassword] = useState('');
@@ -140,7 +143,7 @@ const Recover = ({
       }
 
       await sendSelection("Recover");
-      await sendMnemonic(validMnemonics);
+      await sendMnemonic(validMnemonics, passphrase || null);
       await sendPassword(password);
       navigate("/recover/finish");
 
@@ -182,6 +185,10 @@ const Recover = ({
 
   }
 
+  const onChangePassphrase = (e) => {
+    setPassphrase(e.target.value);
+  }
+
   const onChangePassword = (e) => {
     setShowError(false);
     setPassword(e.target.value);
@@ -207,6 +214,7 @@ const Recover = ({
       checkPasswords,
       onChangeDropDown,
       onChangeWord,
+      onChangePassphrase,
       goBack,
       goForward,
       onChangePassword,
@@ -218,6 +226,7 @@ const Recover = ({
       password,
       mnemonicsValidity,
       mnemonics,
+      passphrase,
       showError
     }} />
   </>);
@@ -8,11 +8,13 @@ const SeedPhrase = () => {
   const {
     onChangeDropDown,
     onChangeWord,
+    onChangePassphrase,
     goBack,
     goForward,
     DROPDOWN_OPTIONS,
     mnemonicsValidity,
-    mnemonics }
+    mnemonics,
+    passphrase }
     = useOutletContext();
 
   return (<>
@@ -21,6 +23,7 @@ const SeedPhrase = () => {
       <h1 className='main-headline'>Reset Wallet</h1>
       <p className='sub-text'>
         You can reset your password by entering your secret recovery phrase.
+        Only English recovery phrases are supported.
       </p>
     </div>
 
@@ -49,6 +52,17 @@ const SeedPhrase = () => {
       })}
     </Form>
 
+    <Form className="ui form adjusted">
+      <Form.Field
+        className="ui form field thin"
+        label="BIP39 passphrase (optional)"
+        control={Input}
+        type="password"
+        value={passphrase}
+        onChange={onChangePassphrase}
+      />
+    </Form>
+
     <div>
       {mnemonicsValidity ?
         <Button primary className="button ui first" onClick={goForward}>Next</Button> :
@@ -0,0 +1,40 @@
+import { Button } from 'semantic-ui-react';
+import "../App.css";
+
+const SetupFailure = ({
+  setupFailure,
+  resolveSetupFailure
+}) => {
+  if (!setupFailure) {
+    return null;
+  }
+
+  const network = setupFailure.network;
+
+  return (<>
+    <div className='tight-header-container'>
+      <h1 className='main-headline padded-bottom-1rem'>Unable to Load Your Wallet</h1>
+      <p className='sub-text'>
+        {setupFailure.error}
+      </p>
+      {network ?
+        <p className='sub-text'>
+          Deleting the <strong>{network}</strong> data rebuilds it from your recovery phrase, which requires a full sync.
+        </p> : null}
+    </div>
+    <Button className="button ui first" onClick={() => resolveSetupFailure("Retry")}>Retry</Button>
+    {network && setupFailure.backup_exists ?
+      <div>
+        <Button className="button ui two" onClick={() => resolveSetupFailure("RestoreBackup")}>Restore Backup</Button>
+      </div> : null}
+    <div>
+      <Button className="button ui two" onClick={() => resolveSetupFailure("RecoverFromMnemonic")}>Recover From Recovery Phrase</Button>
+    </div>
+    {network ?
+      <div>
+        <Button className="button ui danger" onClick={() => resolveSetupFailure("DeleteNetwork")}>Delete {network} Data</Button>
+      </div> : null}
+  </>);
+};
+
+export default SetupFailure;
@@ -9,6 +9,7 @@ const SignIn = ({
   setSignerReady,
   sendSelection,
   getReceivingKeys,
+  getFailedPasswordAttempts,
   receivingKey,
   receivingKeyDisplay,
   sendPassword,
@@ -16,12 +17,18 @@ const SignIn = ({
   startRecover,
   hideWindow,
   loginFailedOccured,
-  setLoginFailedOccured
+  setLoginFailedOccured,
+  passwordDelay,
+  setPasswordDelay,
+  profiles,
+  currentProfile,
+  switchProfile
 }) => {
   const [password, setPassword] = useState('');
   const [passwordInvalid, setPasswordInvalid] = useState(null);
   const [showCopyNotification, setShowCopyNotification] = useState(false);
   const [loading, setLoading] = useState(false);
+  const [failedAttempts, setFailedAttempts] = useState(0);
 
   const onClickSignIn = async () => {
     setLoading(true);
@@ -30,10 +37,12 @@ const SignIn = ({
       await sendSelection("SignIn");
     }
 
+    setPasswordDelay(null);
     const shouldRetry = await sendPassword(password);
 
     if (!shouldRetry) {
 
+      setFailedAttempts(await getFailedPasswordAttempts());
       await getReceivingKeys();
       await endInitialConnectionPhase();
       setPassword('');
@@ -76,15 +85,20 @@ const SignIn = ({
       loading={loading}
       password={password}
       passwordInvalid={passwordInvalid}
+      passwordDelay={passwordDelay}
       onChangePassword={onChangePassword}
       onClickSignIn={onClickSignIn}
       onClickForgotPassword={onClickForgotPassword}
+      profiles={profiles}
+      currentProfile={currentProfile}
+      switchProfile={switchProfile}
     />
     }
     {loginSuccess &&
       <SignInSuccess
       signerReady={signerReady}
       receivingKeyDisplay={receivingKeyDisplay}
+      failedAttempts={failedAttempts}
       showCopyNotification={showCopyNotification}
       onClickCopyZkAddress={onClickCopyZkAddress}
       onClickFinishSignIn={onClickFinishSignIn}
@@ -1,5 +1,7 @@
 import { Button, Input, Form } from 'semantic-ui-react';
 import HyperLinkButton from '../../components/HyperLinkButton';
+import PasswordDelayMessage from '../../components/PasswordDelayMessage';
+import ProfileSelector from '../../components/ProfileSelector';
 import mantaLogo from "../../icons/manta.png";
 import "../../App.css";
 
@@ -7,9 +9,13 @@ const SignInPage = ({
   loading,
   password,
   passwordInvalid,
+  passwordDelay,
   onChangePassword,
   onClickSignIn,
   onClickForgotPassword,
+  profiles,
+  currentProfile,
+  switchProfile
 }) => {
 
   return (<div>
@@ -21,6 +27,11 @@ const SignInPage = ({
       <h1 className='main-headline'>Welcome Back!</h1>
       <p className='sub-text'>Let's connect you to the Web3 privacy hub.</p>
     </div>
+    <ProfileSelector
+      profiles={profiles}
+      currentProfile={currentProfile}
+      switchProfile={switchProfile}
+    />
     <Form.Field>
       <Input
         className='input ui password tall'
@@ -31,10 +42,14 @@ const SignInPage = ({
         error={passwordInvalid}
       />
     </Form.Field>
+    <PasswordDelayMessage passwordDelay={passwordDelay} />
     {
       loading ?
         <Button disabled={true} className="button ui first">Loading</Button>
         :
+        passwordDelay && passwordDelay.lockout ?
+        <Button disabled={true} className="button ui first">Locked</Button>
+        :
         <Button className="button ui first" onClick={onClickSignIn}>
           Unlock
         </Button>
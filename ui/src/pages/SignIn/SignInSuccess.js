@@ -9,6 +9,7 @@ import "../../App.css";
 const SignInSuccess = ({
   signerReady,
   receivingKeyDisplay,
+  failedAttempts,
   showCopyNotification,
   onClickCopyZkAddress,
   onClickFinishSignIn
@@ -22,6 +23,11 @@ const SignInSuccess = ({
     <div>
       <h1 className='main-headline'>Your zkAddress</h1>
     </div>
+    {failedAttempts > 0 &&
+      <p className='sub-text'>
+        {failedAttempts} failed password {failedAttempts === 1 ? 'attempt was' : 'attempts were'} made since your last sign in.
+      </p>
+    }
     <div className='zk-address-container'>
       <p className='sub-text'>{receivingKeyDisplay}</p>
       <CopyButton
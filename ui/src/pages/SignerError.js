@@ -0,0 +1,19 @@
+import "../App.css";
+
+const SignerError = ({
+  signerError
+}) => {
+  return (<>
+    <div className='tight-header-container'>
+      <h1 className='main-headline padded-bottom-1rem'>Unable to Start Manta Signer</h1>
+      <p className='sub-text'>
+        {signerError}
+      </p>
+      <p className='sub-text'>
+        Reinstall Manta Signer or restore the missing files, then restart it.
+      </p>
+    </div>
+  </>);
+};
+
+export default SignerError;
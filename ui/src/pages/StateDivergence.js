@@ -0,0 +1,29 @@
+import { Button } from 'semantic-ui-react';
+import "../App.css";
+
+const StateDivergence = ({
+  stateDivergence,
+  resolveStateDivergence
+}) => {
+  if (!stateDivergence) {
+    return null;
+  }
+
+  return (<>
+    <div className='tight-header-container'>
+      <h1 className='main-headline padded-bottom-1rem'>Wallet Mismatch</h1>
+      <p className='sub-text'>
+        The <strong>{stateDivergence.network}</strong> data of this signer belongs to wallet <strong>{stateDivergence.found}</strong>, but you unlocked wallet <strong>{stateDivergence.expected}</strong>.
+      </p>
+      <p className='sub-text'>
+        Repairing moves the mismatched data aside and rebuilds it from your recovery phrase, which requires a full sync. Keeping it uses the mismatched data until the signer restarts.
+      </p>
+    </div>
+    <Button className="button ui first" onClick={() => resolveStateDivergence(true)}>Repair</Button>
+    <div>
+      <Button className="button ui two" onClick={() => resolveStateDivergence(false)}>Keep</Button>
+    </div>
+  </>);
+};
+
+export default StateDivergence;